@@ -0,0 +1,23 @@
+use std::{io::Write, thread, time::Duration};
+
+use iridescent::{Rainbow, Styled};
+
+fn main() {
+    // A static rainbow, colored the same way every time.
+    println!(
+        "{}",
+        "Taste the rainbow, one character at a time.".rainbow()
+    );
+
+    // Animated banner; the phase moves forward with every frame.
+    let rainbow = Rainbow::new().frequency(0.3).spread(1.0).speed(0.2);
+    let banner = "~*~ iridescent ~*~".to_styled_string().bold();
+
+    for frame in rainbow.frames(banner).take(60) {
+        print!("\r{frame}");
+        std::io::stdout().flush().unwrap();
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    println!();
+}
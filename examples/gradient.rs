@@ -0,0 +1,30 @@
+use iridescent::{Interpolation, Rgb, Styled, StyledString};
+
+fn main() {
+    let stops = [
+        Rgb::from("#ff5f6d"),
+        Rgb::from("#ffc371"),
+        Rgb::from("#47cacc"),
+    ];
+
+    // Blended in the Oklab color space by default.
+    println!(
+        "{}",
+        "Perceptually smooth gradients in Oklab".gradient(&stops)
+    );
+
+    // The other interpolation modes can be picked on a `StyledString`.
+    println!(
+        "{}",
+        StyledString::new("Vivid gradients through the HSL color wheel")
+            .bold()
+            .gradient(&stops, Interpolation::Hsl)
+    );
+
+    println!(
+        "{}",
+        StyledString::new("   Straight sRGB blending behind the text   ")
+            .foreground(Rgb::new(20, 20, 20))
+            .background_gradient(&stops, Interpolation::Srgb)
+    );
+}
@@ -1,6 +1,6 @@
 #[cfg(feature = "random")]
 fn main() {
-    use iridescent::{ForegroundColor, Rgb, Styled, StyledString};
+    use iridescent::{Rgb, Styled, StyledString};
 
     let mut fg_low_bits: Vec<StyledString> = Vec::new();
     let mut bg_low_bits: Vec<StyledString> = Vec::new();
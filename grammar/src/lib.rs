@@ -0,0 +1,233 @@
+#![forbid(unsafe_code)]
+
+//! The grammar of style words, such as `bold`, `bright-red`, `#ff8800` and
+//! `bg:236`, shared by `iridescent` and `iridescent-macros` so that markup
+//! and the macros accept exactly the same words. This crate is an
+//! implementation detail of `iridescent`, and should not be used directly.
+
+/// Represents a color written as a style word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    /// A named color, stored the same way as `ForegroundColor::Simple`: the 8
+    /// base colors are their SGR codes (30 to 37), and the bright colors are
+    /// their palette indexes (8 to 15).
+    Simple(u8),
+    /// An index of the 256 color palette, such as `208`.
+    Index(u8),
+    /// A hexadecimal color, such as `#ff8800`.
+    Rgb(u8, u8, u8),
+}
+
+/// Represents the words of a style tag, such as `b red bg:#202020`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tag {
+    /// The SGR codes of the modes, in the order they were written.
+    pub modes: Vec<u8>,
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+}
+
+/// The names of the 16 named colors.
+const COLOR_NAMES: [(&str, u8); 16] = [
+    ("black", 30),
+    ("red", 31),
+    ("green", 32),
+    ("yellow", 33),
+    ("blue", 34),
+    ("magenta", 35),
+    ("cyan", 36),
+    ("white", 37),
+    ("bright-black", 8),
+    ("bright-red", 9),
+    ("bright-green", 10),
+    ("bright-yellow", 11),
+    ("bright-blue", 12),
+    ("bright-magenta", 13),
+    ("bright-cyan", 14),
+    ("bright-white", 15),
+];
+
+/// Returns the code of a named color, such as 31 for `"red"` or 12 for
+/// `"bright-blue"`. The names are case-insensitive, `_` can be used in place
+/// of `-`, the `-` after `bright` can be left out, and `"gray"` and `"grey"`
+/// are accepted as aliases for `"bright-black"`.
+#[must_use]
+pub fn color_code(name: &str) -> Option<u8> {
+    let mut name = name.to_ascii_lowercase().replace('_', "-");
+
+    // Accept "brightred" as well as "bright-red".
+    if name.starts_with("bright") && !name.starts_with("bright-") {
+        name.insert(6, '-');
+    }
+
+    if name == "gray" || name == "grey" {
+        name = String::from("bright-black");
+    }
+
+    COLOR_NAMES
+        .iter()
+        .find(|(candidate, _)| *candidate == name)
+        .map(|(_, code)| *code)
+}
+
+/// Returns the name of a color code, if it is one of the 16 named colors.
+#[must_use]
+pub fn color_name(code: u8) -> Option<&'static str> {
+    COLOR_NAMES
+        .iter()
+        .find(|(_, candidate)| *candidate == code)
+        .map(|(name, _)| *name)
+}
+
+/// Parses the name of a mode, such as `"bold"` or its short form `"b"`,
+/// into its SGR code. The names are case-insensitive.
+#[must_use]
+pub fn parse_mode(word: &str) -> Option<u8> {
+    let mode = match word.to_ascii_lowercase().as_str() {
+        "bold" | "b" => 1,
+        "dim" => 2,
+        "italic" | "i" => 3,
+        "underline" | "ul" | "u" => 4,
+        "blink" => 5,
+        "invert" | "reverse" => 7,
+        "hidden" => 8,
+        "strike" | "strikethrough" | "s" => 9,
+        _ => return None,
+    };
+
+    Some(mode)
+}
+
+/// Parses a hexadecimal color such as `"#ff8800"` or `"ff8800"` into its red,
+/// green and blue channels, returning `None` if it is not exactly 6
+/// hexadecimal digits.
+#[must_use]
+pub const fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let bytes = match color.as_bytes() {
+        [b'#', rest @ ..] => rest,
+        bytes => bytes,
+    };
+
+    match bytes {
+        [r1, r2, g1, g2, b1, b2] => {
+            match (hex_pair(*r1, *r2), hex_pair(*g1, *g2), hex_pair(*b1, *b2)) {
+                (Some(red), Some(green), Some(blue)) => Some((red, green, blue)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Converts a single hexadecimal digit into its value.
+const fn hex_digit(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Converts two hexadecimal digits into a byte.
+const fn hex_pair(high: u8, low: u8) -> Option<u8> {
+    match (hex_digit(high), hex_digit(low)) {
+        (Some(high), Some(low)) => Some(high * 16 + low),
+        _ => None,
+    }
+}
+
+/// Parses a short hexadecimal color such as `"#f80"`, where each digit is
+/// repeated, into its red, green and blue channels.
+const fn parse_short_hex(color: &str) -> Option<(u8, u8, u8)> {
+    match color.as_bytes() {
+        [b'#', r, g, b] => match (hex_pair(*r, *r), hex_pair(*g, *g), hex_pair(*b, *b)) {
+            (Some(red), Some(green), Some(blue)) => Some((red, green, blue)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Parses a color, which can be a name (`"red"`), a hexadecimal value
+/// (`"#ff8800"` or its short form `"#f80"`) or an index of the 256 color
+/// palette (`"208"`).
+#[must_use]
+pub fn parse_color(word: &str) -> Option<Color> {
+    if word.starts_with('#') {
+        parse_hex(word)
+            .or_else(|| parse_short_hex(word))
+            .map(|(red, green, blue)| Color::Rgb(red, green, blue))
+    } else if let Ok(index) = word.parse::<u8>() {
+        Some(Color::Index(index))
+    } else {
+        color_code(word).map(Color::Simple)
+    }
+}
+
+/// Parses the space separated words of a style tag, each being a mode (`b`,
+/// `bold`), a foreground color (`red`, `#ff8800`, `208`), or a color prefixed
+/// with `fg:` or `bg:`. Returns the first word which is none of these as the
+/// error.
+pub fn parse_tag(tag: &str) -> Result<Tag, &str> {
+    let mut parsed = Tag::default();
+
+    for word in tag.split_whitespace() {
+        if let Some(color) = word.strip_prefix("bg:") {
+            parsed.background = Some(parse_color(color).ok_or(word)?);
+        } else if let Some(color) = word.strip_prefix("fg:") {
+            parsed.foreground = Some(parse_color(color).ok_or(word)?);
+        } else if let Some(mode) = parse_mode(word) {
+            if !parsed.modes.contains(&mode) {
+                parsed.modes.push(mode);
+            }
+        } else {
+            parsed.foreground = Some(parse_color(word).ok_or(word)?);
+        }
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colors() {
+        assert_eq!(color_code("red"), Some(31));
+        assert_eq!(color_code("Bright_Red"), Some(9));
+        assert_eq!(color_code("brightred"), Some(9));
+        assert_eq!(color_code("GREY"), Some(8));
+        assert_eq!(color_code("purple"), None);
+        assert_eq!(color_name(12), Some("bright-blue"));
+
+        assert_eq!(parse_color("31"), Some(Color::Index(31)));
+        assert_eq!(parse_color("RED"), Some(Color::Simple(31)));
+        assert_eq!(parse_color("#FF8800"), Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(parse_color("#F80"), Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(parse_color("#gg0000"), None);
+        assert_eq!(parse_color("#ff80"), None);
+        assert_eq!(parse_hex("ff8800"), Some((255, 136, 0)));
+        assert_eq!(parse_hex("#fff"), None);
+    }
+
+    #[test]
+    fn test_tags() {
+        assert_eq!(
+            parse_tag("B red bg:236 i b"),
+            Ok(Tag {
+                modes: vec![1, 3],
+                foreground: Some(Color::Simple(31)),
+                background: Some(Color::Index(236)),
+            })
+        );
+        assert_eq!(
+            parse_tag("fg:bright_blue 208").map(|tag| tag.foreground),
+            Ok(Some(Color::Index(208)))
+        );
+        assert_eq!(parse_tag(""), Ok(Tag::default()));
+        assert_eq!(parse_tag("bold purple"), Err("purple"));
+        assert_eq!(parse_tag("bg:"), Err("bg:"));
+    }
+}
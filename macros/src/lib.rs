@@ -0,0 +1,266 @@
+#![forbid(unsafe_code)]
+
+//! Compile-time validated styling macros for `iridescent`. These are
+//! re-exported by `iridescent` when its `macros` feature is enabled, and should
+//! be used from there.
+
+use iridescent_grammar::{parse_hex, Color};
+use proc_macro::{Delimiter, Group, Literal, Spacing, Span, TokenStream, TokenTree};
+
+use crate::template::{parse_spec, transform};
+
+/// Parsing of style specifications and format string templates.
+mod template;
+
+/// Returns a `compile_error!` invocation with the message, pointing at the
+/// given span.
+fn error(message: &str, span: Span) -> TokenStream {
+    let tokens: TokenStream = format!("::core::compile_error!({message:?})")
+        .parse()
+        .expect("compile_error! invocation is valid");
+
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
+
+/// Returns the value of a string literal, along with whether it is a raw
+/// string. Only the simple escape sequences are unescaped, as the values
+/// handled here (colors and style names) never contain anything else.
+fn string_value(literal: &Literal) -> Option<(String, bool)> {
+    let source = literal.to_string();
+
+    if let Some(rest) = source.strip_prefix('r') {
+        let hashes = rest.len() - rest.trim_start_matches('#').len();
+        let value = &rest[hashes + 1..rest.len() - hashes - 1];
+
+        return Some((value.to_string(), true));
+    }
+
+    let value = source.strip_prefix('"')?.strip_suffix('"')?;
+
+    Some((value.to_string(), false))
+}
+
+/// Returns the single string literal passed to a macro.
+fn single_literal(input: TokenStream) -> Result<(String, Span), TokenStream> {
+    let mut tokens = input.into_iter();
+
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => match string_value(&literal) {
+            Some((value, _)) => Ok((value, literal.span())),
+            None => Err(error("expected a string literal", literal.span())),
+        },
+        (Some(token), _) => Err(error("expected a single string literal", token.span())),
+        (None, _) => Err(error("expected a string literal", Span::call_site())),
+    }
+}
+
+fn color_tokens(color: Color, kind: &str) -> String {
+    match color {
+        Color::Simple(code) => format!("::iridescent::{kind}::Simple({code}u8)"),
+        Color::Index(index) => format!("::iridescent::{kind}::indexed({index}u8)"),
+        Color::Rgb(red, green, blue) => format!(
+            "::iridescent::{kind}::Rgb(::iridescent::Rgb {{ red: {red}u8, green: {green}u8, blue: {blue}u8 }})"
+        ),
+    }
+}
+
+/// Creates an `Rgb` color from a hexadecimal string literal, which is
+/// validated at compile time.
+///
+/// ```ignore
+/// let orange = rgb!("#ff8800");
+/// ```
+#[proc_macro]
+pub fn rgb(input: TokenStream) -> TokenStream {
+    let (value, span) = match single_literal(input) {
+        Ok(literal) => literal,
+        Err(error) => return error,
+    };
+
+    match parse_hex(&value) {
+        Some((red, green, blue)) => {
+            format!("::iridescent::Rgb {{ red: {red}u8, green: {green}u8, blue: {blue}u8 }}")
+                .parse()
+                .expect("Rgb expression is valid")
+        }
+        None => error(
+            &format!(
+                "invalid hex color `{value}`, expected 6 hexadecimal digits such as `#ff8800`"
+            ),
+            span,
+        ),
+    }
+}
+
+/// Creates a `Style` from a string literal of space separated words, which is
+/// validated at compile time. The words are the same as in `iridescent`'s
+/// markup: a mode (`bold` or `b`, `dim`, `italic` or `i`, `underline` or `u`,
+/// `blink`, `invert`, `hidden`, `strike` or `s`), a color name (`red`,
+/// `bright-blue`), a hexadecimal color (`#ff8800`), an 8-bit color index
+/// (`208`), or a color prefixed with `fg:` or `bg:` (`bg:236`). The expansion
+/// can initialize `const` items.
+///
+/// ```ignore
+/// let warning = style!("bold yellow bg:#202020");
+/// ```
+#[proc_macro]
+pub fn style(input: TokenStream) -> TokenStream {
+    let (value, span) = match single_literal(input) {
+        Ok(literal) => literal,
+        Err(error) => return error,
+    };
+
+    let spec = match parse_spec(&value) {
+        Ok(spec) => spec,
+        Err(message) => return error(&message, span),
+    };
+
+    let mut expression = String::from("::iridescent::Style::new()");
+    for mode in &spec.modes {
+        expression.push_str(match mode {
+            1 => ".bold()",
+            2 => ".dim()",
+            3 => ".italic()",
+            4 => ".underline()",
+            5 => ".blink()",
+            7 => ".invert()",
+            8 => ".hidden()",
+            _ => ".strike()",
+        });
+    }
+
+    if let Some(color) = spec.foreground {
+        expression.push_str(&format!(
+            ".with_foreground({})",
+            color_tokens(color, "ForegroundColor")
+        ));
+    }
+
+    if let Some(color) = spec.background {
+        expression.push_str(&format!(
+            ".with_background({})",
+            color_tokens(color, "BackgroundColor")
+        ));
+    }
+
+    expression.parse().expect("Style expression is valid")
+}
+
+/// Expands into a call of a standard formatting macro, with the style tags in
+/// the format string replaced by escape sequences. If `has_target` is set, the
+/// format string follows a first argument, such as the writer of `write!`.
+fn expand(name: &str, input: TokenStream, has_target: bool) -> TokenStream {
+    let mut tokens: Vec<TokenTree> = input.into_iter().collect();
+
+    // The format string is the first token, or the first token after the
+    // top-level comma separating it from the target.
+    let index = if has_target {
+        match tokens
+            .iter()
+            .position(|token| matches!(token, TokenTree::Punct(p) if p.as_char() == ',' && p.spacing() == Spacing::Alone))
+        {
+            Some(comma) => comma + 1,
+            None => return error("expected a format string", Span::call_site()),
+        }
+    } else {
+        0
+    };
+
+    let literal = match tokens.get(index) {
+        Some(TokenTree::Literal(literal)) => literal.clone(),
+        Some(token) => return error("format argument must be a string literal", token.span()),
+        None => return error("expected a format string", Span::call_site()),
+    };
+
+    let Some((template, raw)) = string_value(&literal) else {
+        return error("format argument must be a string literal", literal.span());
+    };
+
+    let replaced = match transform(&template, if raw { "\u{1b}" } else { "\\u{1b}" }, raw) {
+        Ok(replaced) => replaced,
+        Err(message) => return error(&message, literal.span()),
+    };
+
+    let mut replacement = if raw {
+        Literal::string(&replaced)
+    } else {
+        match format!("\"{replaced}\"").parse::<TokenStream>() {
+            Ok(stream) => match stream.into_iter().next() {
+                Some(TokenTree::Literal(literal)) => literal,
+                _ => return error("invalid format string", literal.span()),
+            },
+            Err(_) => return error("invalid format string", literal.span()),
+        }
+    };
+    replacement.set_span(literal.span());
+    tokens[index] = TokenTree::Literal(replacement);
+
+    let mut output: TokenStream = format!("::std::{name}!")
+        .parse()
+        .expect("macro path is valid");
+    output.extend([TokenTree::Group(Group::new(
+        Delimiter::Parenthesis,
+        tokens.into_iter().collect(),
+    ))]);
+
+    output
+}
+
+/// Like `format!`, but with style tags such as `{red}` or `{bold #ff8800}`
+/// in the format string, closed by `{/}`. Tags are validated and turned into
+/// escape sequences at compile time. See `style!` for the accepted words.
+///
+/// Every placeholder made of style words is a style tag, except for positions
+/// such as `{0}` and single letters such as `{b}`, which remain formatting
+/// arguments. A variable named like a style is printed with an empty format
+/// spec, such as `{red:}`, and literal braces are written as `{{` and `}}`.
+///
+/// ```ignore
+/// let message = cformat!("{red}Error{/}: {bold}{}{/}", path);
+/// ```
+#[proc_macro]
+pub fn cformat(input: TokenStream) -> TokenStream {
+    expand("format", input, false)
+}
+
+/// Like `print!`, but with style tags in the format string. See `cformat!`.
+#[proc_macro]
+pub fn cprint(input: TokenStream) -> TokenStream {
+    expand("print", input, false)
+}
+
+/// Like `println!`, but with style tags in the format string. See `cformat!`.
+#[proc_macro]
+pub fn cprintln(input: TokenStream) -> TokenStream {
+    expand("println", input, false)
+}
+
+/// Like `eprint!`, but with style tags in the format string. See `cformat!`.
+#[proc_macro]
+pub fn ceprint(input: TokenStream) -> TokenStream {
+    expand("eprint", input, false)
+}
+
+/// Like `eprintln!`, but with style tags in the format string. See `cformat!`.
+#[proc_macro]
+pub fn ceprintln(input: TokenStream) -> TokenStream {
+    expand("eprintln", input, false)
+}
+
+/// Like `write!`, but with style tags in the format string. See `cformat!`.
+#[proc_macro]
+pub fn cwrite(input: TokenStream) -> TokenStream {
+    expand("write", input, true)
+}
+
+/// Like `writeln!`, but with style tags in the format string. See `cformat!`.
+#[proc_macro]
+pub fn cwriteln(input: TokenStream) -> TokenStream {
+    expand("writeln", input, true)
+}
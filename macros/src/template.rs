@@ -0,0 +1,240 @@
+use iridescent_grammar::{parse_tag, Color, Tag};
+
+/// Parses a style specification made of space separated words, each being a
+/// mode, a color, or a color prefixed with `fg:` or `bg:`, using the same
+/// grammar as `iridescent`'s markup.
+pub(crate) fn parse_spec(spec: &str) -> Result<Tag, String> {
+    let tag = parse_tag(spec).map_err(|word| {
+        let color = word
+            .strip_prefix("bg:")
+            .or_else(|| word.strip_prefix("fg:"))
+            .unwrap_or(word);
+
+        if color.starts_with('#') {
+            format!(
+                "invalid hex color `{color}`, expected 3 or 6 hexadecimal digits such as `#ff8800`"
+            )
+        } else {
+            format!("unknown style `{word}`")
+        }
+    })?;
+
+    if tag == Tag::default() {
+        return Err(String::from("empty style"));
+    }
+
+    Ok(tag)
+}
+
+/// Returns the SGR parameters of a style, in the same order that
+/// `StyledString` renders them.
+fn sgr(tag: &Tag) -> Vec<String> {
+    let mut sequence: Vec<String> = tag.modes.iter().map(u8::to_string).collect();
+
+    let mut push_color = |color: Option<Color>, position: u8, offset: u8| match color {
+        // The first 8 palette indexes are stored as the base colors, as
+        // `ForegroundColor::indexed` does.
+        Some(Color::Simple(code @ 30..=37)) => sequence.push((code + offset).to_string()),
+        Some(Color::Index(index @ 0..=7)) => sequence.push((index + 30 + offset).to_string()),
+        Some(Color::Simple(index) | Color::Index(index)) => {
+            sequence.push(format!("{position};5;{index}"));
+        }
+        Some(Color::Rgb(red, green, blue)) => {
+            sequence.push(format!("{position};2;{red};{green};{blue}"));
+        }
+        None => {}
+    };
+
+    push_color(tag.foreground, 38, 0);
+    push_color(tag.background, 48, 10);
+
+    sequence
+}
+
+/// Returns a style with another style layered on top of it.
+fn patch(tag: &Tag, other: &Tag) -> Tag {
+    let mut patched = tag.clone();
+
+    for mode in &other.modes {
+        if !patched.modes.contains(mode) {
+            patched.modes.push(*mode);
+        }
+    }
+
+    patched.foreground = other.foreground.or(tag.foreground);
+    patched.background = other.background.or(tag.background);
+
+    patched
+}
+
+/// Returns whether a `{...}` placeholder is a style tag, such as `{red}` or
+/// `{b #ff8800}`, rather than a formatting argument. Positions such as `{0}`
+/// and single letters such as `{b}` or `{i}` are always formatting arguments.
+fn is_tag(contents: &str) -> bool {
+    let argument = contents.chars().count() == 1 || contents.parse::<usize>().is_ok();
+
+    !argument && parse_spec(contents).is_ok()
+}
+
+/// Returns whether a `{...}` placeholder can be a formatting argument, which
+/// is an optional name or position followed by an optional format spec, such
+/// as `{}`, `{0}` or `{name:?}`.
+fn is_argument(contents: &str) -> bool {
+    let name = contents.split(':').next().unwrap_or_default();
+
+    name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Replaces the style tags in a format string with escape sequences, leaving
+/// formatting arguments such as `{}`, `{0}` and `{name:?}` untouched.
+///
+/// Tags such as `{red}` are closed with `{/}`, which restores the styles of
+/// any tags still open around it. `escape` is inserted before each sequence,
+/// and is either the escape character itself or its escaped source form,
+/// `\u{1b}`.
+pub(crate) fn transform(template: &str, escape: &str, raw: bool) -> Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut stack: Vec<(String, Tag)> = Vec::new();
+    let mut chars = template.chars().peekable();
+
+    let open = |output: &mut String, tag: &Tag| {
+        output.push_str(escape);
+        output.push('[');
+        output.push_str(&sgr(tag).join(";"));
+        output.push('m');
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            // Escapes in the source form of a string are copied as is, so that
+            // `\u{..}` is not mistaken for a tag.
+            '\\' if !raw => {
+                output.push(c);
+                if let Some(next) = chars.next() {
+                    output.push(next);
+                    if next == 'u' && chars.peek() == Some(&'{') {
+                        for c in chars.by_ref() {
+                            output.push(c);
+                            if c == '}' {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push_str("{{");
+            }
+            '{' => {
+                let mut contents = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                    contents.push(c);
+                }
+
+                let trimmed = contents.trim();
+                if closed && trimmed == "/" {
+                    if stack.pop().is_none() {
+                        return Err(String::from("`{/}` does not close any style"));
+                    }
+
+                    output.push_str(escape);
+                    output.push_str("[0m");
+                    if let Some((_, tag)) = stack.last() {
+                        open(&mut output, tag);
+                    }
+                } else if closed && (is_tag(trimmed) || !is_argument(trimmed)) {
+                    // Placeholders which cannot be formatting arguments, such
+                    // as `{bold purple}`, report why they are not a style.
+                    let tag = parse_spec(trimmed)?;
+                    let tag = match stack.last() {
+                        Some((_, outer)) => patch(outer, &tag),
+                        None => tag,
+                    };
+
+                    open(&mut output, &tag);
+                    stack.push((trimmed.to_string(), tag));
+                } else {
+                    // Leave formatting arguments, and invalid placeholders for
+                    // the formatting macro to report.
+                    output.push('{');
+                    output.push_str(&contents);
+                    if closed {
+                        output.push('}');
+                    }
+                }
+            }
+            c => output.push(c),
+        }
+    }
+
+    if let Some((tag, _)) = stack.last() {
+        return Err(format!("style `{{{tag}}}` is never closed with `{{/}}`"));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform() {
+        let escape = "\\u{1b}";
+
+        assert_eq!(
+            transform("{red}Error{/}: {bold}{}{/}", escape, false).unwrap(),
+            "\\u{1b}[31mError\\u{1b}[0m: \\u{1b}[1m{}\\u{1b}[0m"
+        );
+        assert_eq!(
+            transform("{b bg:#202020}a{i 208}b{/}c{/}", escape, false).unwrap(),
+            "\\u{1b}[1;48;2;32;32;32ma\\u{1b}[1;3;38;5;208;48;2;32;32;32mb\\u{1b}[0m\\u{1b}[1;48;2;32;32;32mc\\u{1b}[0m"
+        );
+        assert_eq!(
+            transform(
+                "{{red}} {} {0} {name:?} {x:>5} {red:} \\u{7b}",
+                escape,
+                false
+            )
+            .unwrap(),
+            "{{red}} {} {0} {name:?} {x:>5} {red:} \\u{7b}"
+        );
+        assert_eq!(
+            transform("{b} {i} {s} {u} {1}", escape, false).unwrap(),
+            "{b} {i} {s} {u} {1}"
+        );
+        assert_eq!(
+            transform("{Bright_Red}x{/}{fg:1 bg:31}y{/}", "\u{1b}", true).unwrap(),
+            "\u{1b}[38;5;9mx\u{1b}[0m\u{1b}[31;48;5;31my\u{1b}[0m"
+        );
+
+        assert!(transform("{#gg0000}x{/}", escape, false).is_err());
+        assert!(transform("{bold purple}x{/}", escape, false).is_err());
+        assert!(transform("{red}x", escape, false).is_err());
+        assert!(transform("x{/}", escape, false).is_err());
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        assert_eq!(
+            parse_spec("BOLD brightred").unwrap().foreground,
+            Some(Color::Simple(9))
+        );
+        assert_eq!(
+            parse_spec("fg:#gg0000"),
+            Err(String::from(
+                "invalid hex color `#gg0000`, expected 3 or 6 hexadecimal digits such as `#ff8800`"
+            ))
+        );
+        assert_eq!(
+            parse_spec("bold purple"),
+            Err(String::from("unknown style `purple`"))
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    env,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock,
+    },
+};
+
+use crate::{
+    background::BackgroundColor,
+    color::{Rgb, Simple},
+    foreground::ForegroundColor,
+};
+
+/// Represents whether the terminal has a light or a dark background.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Appearance {
+    Light,
+    #[default]
+    Dark,
+}
+
+impl Appearance {
+    /// Returns the appearance of a terminal with the given background color,
+    /// such as one returned by `query_background`. A background is light when
+    /// black text on it is more readable than white text.
+    #[must_use]
+    pub fn from_background(background: Rgb) -> Self {
+        let black = Rgb::new(0, 0, 0);
+        let white = Rgb::new(255, 255, 255);
+
+        if black.contrast_ratio(&background) > white.contrast_ratio(&background) {
+            Appearance::Light
+        } else {
+            Appearance::Dark
+        }
+    }
+
+    /// Returns the appearance described by the value of the `COLORFGBG`
+    /// environment variable, such as `"15;0"`, whose last field is the palette
+    /// index of the background. Returns `None` if the value is malformed.
+    #[must_use]
+    pub fn from_colorfgbg(value: &str) -> Option<Self> {
+        let background = value.rsplit(';').next()?.trim();
+
+        match background.parse::<u8>() {
+            Ok(index) if index < 16 => Some(Appearance::from_background(Rgb::from(Simple(index)))),
+            // Some terminals report the default color instead of an index.
+            _ if background == "default" => Some(Appearance::Dark),
+            _ => None,
+        }
+    }
+
+    /// Detects the appearance of the terminal from the `COLORFGBG`
+    /// environment variable, falling back to `Dark` when it cannot be
+    /// determined. It never talks to the terminal, see `Appearance::query`
+    /// for that.
+    #[must_use]
+    pub fn detect() -> Self {
+        env::var("COLORFGBG")
+            .ok()
+            .and_then(|value| Appearance::from_colorfgbg(&value))
+            .unwrap_or_default()
+    }
+
+    /// Asks the controlling terminal for its background color, waiting at
+    /// most 100 milliseconds for the reply, and returns the appearance it
+    /// describes. Echo and line buffering are turned off for the duration of
+    /// the query. Returns `None` if there is no terminal, the process runs in
+    /// the background, or the terminal does not reply in time.
+    ///
+    /// This is never done implicitly. Pass the result to `set_appearance` to
+    /// resolve `Adaptive` colors against it:
+    ///
+    /// ```ignore
+    /// set_appearance(Appearance::query());
+    /// ```
+    #[cfg(all(unix, feature = "query"))]
+    #[must_use]
+    pub fn query() -> Option<Self> {
+        use std::{fs::OpenOptions, time::Duration};
+
+        use rustix::{
+            process::getpgrp,
+            termios::{tcgetattr, tcgetpgrp, tcsetattr, LocalModes, OptionalActions},
+        };
+
+        use crate::query::query_background;
+
+        let mut tty = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .ok()?;
+
+        // Changing the mode of the terminal from a background job would stop
+        // the whole process.
+        if tcgetpgrp(&tty).ok()? != getpgrp() {
+            return None;
+        }
+
+        let original = tcgetattr(&tty).ok()?;
+        let mut quiet = original.clone();
+        quiet.local_modes -= LocalModes::ICANON | LocalModes::ECHO;
+        tcsetattr(&tty, OptionalActions::Now, &quiet).ok()?;
+
+        let background = query_background(&mut tty, Duration::from_millis(100));
+        let _ = tcsetattr(&tty, OptionalActions::Now, &original);
+
+        background.ok().flatten().map(Appearance::from_background)
+    }
+}
+
+/// Represents a color with one variant for light backgrounds and another for
+/// dark backgrounds. It is resolved against the current `appearance` whenever
+/// it is displayed, so the same style stays readable on either kind of
+/// terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Adaptive {
+    /// The color used on light backgrounds.
+    pub light: Rgb,
+    /// The color used on dark backgrounds.
+    pub dark: Rgb,
+}
+
+impl Adaptive {
+    #[must_use]
+    pub const fn new(light: Rgb, dark: Rgb) -> Self {
+        Self { light, dark }
+    }
+
+    /// Returns the variant of the color for the given appearance.
+    #[must_use]
+    pub const fn resolve(&self, appearance: Appearance) -> Rgb {
+        match appearance {
+            Appearance::Light => self.light,
+            Appearance::Dark => self.dark,
+        }
+    }
+}
+
+impl From<Adaptive> for ForegroundColor {
+    fn from(color: Adaptive) -> Self {
+        ForegroundColor::Adaptive(color)
+    }
+}
+
+impl From<Adaptive> for BackgroundColor {
+    fn from(color: Adaptive) -> Self {
+        BackgroundColor::Adaptive(color)
+    }
+}
+
+impl ForegroundColor {
+    /// Returns the color with any `Adaptive` color resolved against the
+    /// current appearance.
+    pub(crate) fn resolved(self) -> Self {
+        match self {
+            ForegroundColor::Adaptive(color) => ForegroundColor::Rgb(color.resolve(appearance())),
+            color => color,
+        }
+    }
+}
+
+impl BackgroundColor {
+    /// Returns the color with any `Adaptive` color resolved against the
+    /// current appearance.
+    pub(crate) fn resolved(self) -> Self {
+        match self {
+            BackgroundColor::Adaptive(color) => BackgroundColor::Rgb(color.resolve(appearance())),
+            color => color,
+        }
+    }
+}
+
+// The appearance configured with `set_appearance`, where zero means it is
+// detected instead.
+static APPEARANCE: AtomicU8 = AtomicU8::new(0);
+
+// The detected appearance, as the environment is only read once.
+static DETECTED: OnceLock<Appearance> = OnceLock::new();
+
+/// Sets the appearance that `Adaptive` colors are resolved against, or goes
+/// back to detecting it with `None`. See `Appearance::detect`.
+pub fn set_appearance(appearance: Option<Appearance>) {
+    let value = match appearance {
+        None => 0,
+        Some(Appearance::Light) => 1,
+        Some(Appearance::Dark) => 2,
+    };
+
+    APPEARANCE.store(value, Ordering::Relaxed);
+}
+
+/// Returns the appearance that `Adaptive` colors are resolved against, which
+/// is either the one set with `set_appearance`, or detected once from the
+/// environment. See `Appearance::detect`.
+#[must_use]
+pub fn appearance() -> Appearance {
+    match APPEARANCE.load(Ordering::Relaxed) {
+        1 => Appearance::Light,
+        2 => Appearance::Dark,
+        _ => *DETECTED.get_or_init(Appearance::detect),
+    }
+}
@@ -0,0 +1,216 @@
+use std::{io::Read, iter::Peekable, str::Chars};
+
+use crate::{html::Html, style::Style, styled_text::StyledText};
+
+impl StyledText {
+    /// Parses text containing ANSI escape sequences, such as the captured
+    /// output of a command, into spans styled by its SGR sequences.
+    ///
+    /// Every other escape sequence, such as cursor movement or hyperlinks,
+    /// is removed, along with control characters other than newlines, tabs
+    /// and carriage returns. Text written after a carriage return replaces
+    /// the line before it, as progress bars do.
+    ///
+    /// Like in a terminal, SGR parameters which cannot be parsed, such as
+    /// `300` in `\x1b[1;300m`, are skipped while the rest of the sequence is
+    /// applied. Colors can separate their parameters with `;` or `:`, as in
+    /// `38:2:255:136:0`, and other sub-parameters are read for underlines
+    /// only, where any `4:n` other than `4:0` is a plain underline.
+    #[must_use = "this returns the parsed text, without printing it"]
+    pub fn from_ansi(input: &str) -> StyledText {
+        let mut text = StyledText::new();
+        let mut style = Style::new();
+        let mut buffer = String::new();
+        let mut chars = input.chars().peekable();
+        let mut carriage_return = false;
+
+        while let Some(character) = chars.next() {
+            match character {
+                '\x1b' => {
+                    let Some(parameters) = escape_sequence(&mut chars) else {
+                        continue;
+                    };
+
+                    // The modes which are turned off are simply absent from
+                    // the terminal's current style.
+                    let next = Style {
+                        disabled: 0,
+                        ..apply_sgr(style, &parameters)
+                    };
+
+                    if next != style && !buffer.is_empty() {
+                        text.push(style.apply(&buffer));
+                        buffer.clear();
+                    }
+                    style = next;
+                }
+                '\r' => carriage_return = true,
+                '\n' => {
+                    carriage_return = false;
+                    buffer.push(character);
+                }
+                character if character.is_control() && character != '\t' => {}
+                character => {
+                    if carriage_return {
+                        discard_line(&mut text, &mut buffer);
+                        carriage_return = false;
+                    }
+                    buffer.push(character);
+                }
+            }
+        }
+
+        if !buffer.is_empty() {
+            text.push(style.apply(&buffer));
+        }
+
+        text
+    }
+
+    /// Reads all of the input and parses it like `StyledText::from_ansi`.
+    /// Invalid UTF-8 is replaced with `U+FFFD`.
+    pub fn from_ansi_reader(mut reader: impl Read) -> std::io::Result<StyledText> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        Ok(StyledText::from_ansi(&String::from_utf8_lossy(&bytes)))
+    }
+}
+
+impl Html {
+    /// Renders text containing ANSI escape sequences as HTML. Escape
+    /// sequences other than colors and modes are removed, and the text is
+    /// escaped, so the output is safe to embed in a page.
+    #[must_use]
+    pub fn render_ansi(&self, input: &str) -> String {
+        self.render(&StyledText::from_ansi(input))
+    }
+
+    /// Reads all of the input and renders it like `Html::render_ansi`.
+    pub fn render_ansi_reader(&self, reader: impl Read) -> std::io::Result<String> {
+        Ok(self.render(&StyledText::from_ansi_reader(reader)?))
+    }
+}
+
+/// Removes the text written since the start of the current line, both from
+/// the pending text and from the spans already pushed.
+fn discard_line(text: &mut StyledText, buffer: &mut String) {
+    if let Some(newline) = buffer.rfind('\n') {
+        buffer.truncate(newline + 1);
+        return;
+    }
+
+    buffer.clear();
+    while let Some(span) = text.spans.last_mut() {
+        match span.text.rfind('\n') {
+            Some(newline) => {
+                span.text.truncate(newline + 1);
+                break;
+            }
+            None => {
+                text.spans.pop();
+            }
+        }
+    }
+}
+
+/// Applies the parameters of an SGR sequence to a style one at a time, with
+/// extended colors taking the parameters after them, so that a parameter
+/// which cannot be parsed only skips itself.
+fn apply_sgr(mut style: Style, parameters: &str) -> Style {
+    let mut parameters = parameters.split(';');
+
+    while let Some(parameter) = parameters.next() {
+        let code = parameter.trim();
+        let unit = if code.contains(':') {
+            sub_parameters(code)
+        } else if matches!(code, "38" | "48" | "58") {
+            let kind = parameters.next().unwrap_or_default();
+            let count = match kind.trim() {
+                "5" => 1,
+                "2" => 3,
+                _ => 0,
+            };
+
+            // A color cut short by the end of the sequence is skipped, and
+            // underline colors are not part of a `Style`.
+            let values: Vec<&str> = parameters.by_ref().take(count).collect();
+            (values.len() == count && code != "58")
+                .then(|| format!("{code};{kind};{}", values.join(";")))
+        } else {
+            Some(code.to_string())
+        };
+
+        if let Some(next) = unit.and_then(|unit| style.with_sgr(&unit)) {
+            style = next;
+        }
+    }
+
+    style
+}
+
+/// Converts a parameter with `:` separated sub-parameters, such as
+/// `38:2:255:136:0` or the `38:2::255:136:0` form with an empty color space,
+/// into the `;` separated form read by `Style::with_sgr`.
+fn sub_parameters(parameter: &str) -> Option<String> {
+    let parts: Vec<&str> = parameter.split(':').collect();
+
+    match parts.as_slice() {
+        [code @ ("38" | "48"), "5", index] => Some(format!("{code};5;{index}")),
+        [code @ ("38" | "48"), "2", red, green, blue]
+        | [code @ ("38" | "48"), "2", _, red, green, blue] => {
+            Some(format!("{code};2;{red};{green};{blue}"))
+        }
+        ["4", "0"] => Some(String::from("24")),
+        ["4", _] => Some(String::from("4")),
+        _ => None,
+    }
+}
+
+/// Consumes an escape sequence after its `ESC`, returning the parameters if
+/// it is an SGR sequence.
+fn escape_sequence(chars: &mut Peekable<Chars<'_>>) -> Option<String> {
+    match chars.next()? {
+        // Control sequences: parameter bytes, intermediate bytes and a final
+        // byte, where `m` sets the style.
+        '[' => {
+            let mut parameters = String::new();
+            while let Some(character) = chars.next_if(|c| ('\x30'..='\x3f').contains(c)) {
+                parameters.push(character);
+            }
+
+            let mut intermediates = false;
+            while chars.next_if(|c| ('\x20'..='\x2f').contains(c)).is_some() {
+                intermediates = true;
+            }
+
+            let last = chars.next_if(|c| ('\x40'..='\x7e').contains(c))?;
+
+            // Private sequences, such as `\x1b[?25l`, start with `<`, `=`,
+            // `>` or `?`.
+            let private = parameters.starts_with(['<', '=', '>', '?']);
+            (last == 'm' && !intermediates && !private).then_some(parameters)
+        }
+        // Strings, such as OSC 8 hyperlinks and window titles, which end with
+        // a bell (for OSC) or a string terminator.
+        ']' | 'P' | 'X' | '^' | '_' => {
+            while let Some(character) = chars.next() {
+                match character {
+                    '\x07' => break,
+                    '\x1b' if chars.next_if_eq(&'\\').is_some() => break,
+                    _ => {}
+                }
+            }
+            None
+        }
+        // Other escape sequences, such as `\x1b(B`, have intermediate bytes
+        // followed by a final byte.
+        character => {
+            if ('\x20'..='\x2f').contains(&character) {
+                while chars.next_if(|c| ('\x20'..='\x2f').contains(c)).is_some() {}
+                chars.next_if(|c| ('\x30'..='\x7e').contains(c));
+            }
+            None
+        }
+    }
+}
@@ -1,12 +1,53 @@
-use crate::color::Rgb;
+use crate::{
+    adaptive::{appearance, Adaptive},
+    color::{xterm_rgb, Rgb, Simple},
+    constants::{BLACK, WHITE},
+};
 
 /// Represents a color that will be applied to the background of a
 /// `StyledString`.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum BackgroundColor {
+    #[default]
     Empty,
     Simple(u8),
+    /// A color of the 256 color palette by its index, which is always written
+    /// as `48;5;index`. Unlike `Simple`, where 30 to 37 are the base
+    /// colors, it can hold every index. See `BackgroundColor::indexed`.
+    Indexed(u8),
     Rgb(Rgb),
+    /// A color that depends on whether the terminal background is light or
+    /// dark. See `Adaptive`.
+    Adaptive(Adaptive),
+}
+
+impl BackgroundColor {
+    /// Returns the color at an index of the 256 color palette, such as a
+    /// color number read from a configuration file. The first 8 indexes are
+    /// stored as the base colors, such as `RED` for 1, and the indexes 30 to
+    /// 37 as `Indexed`, so that every index has a single representation.
+    #[must_use]
+    pub const fn indexed(index: u8) -> Self {
+        match index {
+            0..=7 => BackgroundColor::Simple(index + BLACK),
+            BLACK..=WHITE => BackgroundColor::Indexed(index),
+            index => BackgroundColor::Simple(index),
+        }
+    }
+
+    /// Returns the color as an `Rgb` value, or `None` if no color is set.
+    /// `Simple` colors are approximated using the default xterm palette, and
+    /// `Adaptive` colors are resolved against the current appearance.
+    #[must_use]
+    pub fn to_rgb(&self) -> Option<Rgb> {
+        match self {
+            BackgroundColor::Empty => None,
+            BackgroundColor::Simple(color) => Some(Rgb::from(Simple(*color))),
+            BackgroundColor::Indexed(index) => Some(xterm_rgb(*index)),
+            BackgroundColor::Rgb(color) => Some(*color),
+            BackgroundColor::Adaptive(color) => Some(color.resolve(appearance())),
+        }
+    }
 }
 
 impl From<u8> for BackgroundColor {
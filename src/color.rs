@@ -1,6 +1,15 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use iridescent_grammar::{color_code, color_name, parse_hex};
+
+use crate::{
+    color_space::{gamut_map, normalize_hue, Oklab, Oklch},
+    constants::{BLACK, WHITE},
+};
+
 /// Simple new-type wrapper around u8 for providing extra API's when working
 /// with colors.
-pub struct Simple(u8);
+pub struct Simple(pub(crate) u8);
 
 impl Simple {
     /// Returns a single, random color value for use with 8-bit ANSI escape
@@ -11,8 +20,119 @@ impl Simple {
     pub fn random() -> u8 {
         rand::random::<u8>()
     }
+
+    /// Returns a single, random color value for use with 8-bit ANSI escape
+    /// sequences, drawn from the given random number generator. Use a seeded
+    /// generator for reproducible colors.
+    ///
+    /// Requires the `random` feature to be enabled.
+    #[cfg(feature = "random")]
+    pub fn random_with<R: rand::Rng + ?Sized>(rng: &mut R) -> u8 {
+        rng.gen::<u8>()
+    }
 }
 
+impl Simple {
+    /// Returns the color with the given name, such as `"red"` or
+    /// `"bright-blue"`. The names are case-insensitive, and `"gray"` and
+    /// `"grey"` are accepted as aliases for `"bright-black"`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Simple> {
+        color_code(name).map(Simple)
+    }
+
+    /// Returns the name of the color, if it is one of the 16 named colors.
+    #[must_use]
+    pub fn name(&self) -> Option<&'static str> {
+        color_name(self.0)
+    }
+}
+
+/// The default xterm values of the 16 base colors, used to approximate how a
+/// `Simple` color looks when the terminal's real palette is unknown.
+pub(crate) const XTERM_BASE_COLORS: [Rgb; 16] = [
+    Rgb {
+        red: 0,
+        green: 0,
+        blue: 0,
+    },
+    Rgb {
+        red: 205,
+        green: 0,
+        blue: 0,
+    },
+    Rgb {
+        red: 0,
+        green: 205,
+        blue: 0,
+    },
+    Rgb {
+        red: 205,
+        green: 205,
+        blue: 0,
+    },
+    Rgb {
+        red: 0,
+        green: 0,
+        blue: 238,
+    },
+    Rgb {
+        red: 205,
+        green: 0,
+        blue: 205,
+    },
+    Rgb {
+        red: 0,
+        green: 205,
+        blue: 205,
+    },
+    Rgb {
+        red: 229,
+        green: 229,
+        blue: 229,
+    },
+    Rgb {
+        red: 127,
+        green: 127,
+        blue: 127,
+    },
+    Rgb {
+        red: 255,
+        green: 0,
+        blue: 0,
+    },
+    Rgb {
+        red: 0,
+        green: 255,
+        blue: 0,
+    },
+    Rgb {
+        red: 255,
+        green: 255,
+        blue: 0,
+    },
+    Rgb {
+        red: 92,
+        green: 92,
+        blue: 255,
+    },
+    Rgb {
+        red: 255,
+        green: 0,
+        blue: 255,
+    },
+    Rgb {
+        red: 0,
+        green: 255,
+        blue: 255,
+    },
+    Rgb {
+        red: 255,
+        green: 255,
+        blue: 255,
+    },
+];
+
 /// Represents a RGB (red-green-blue) color value as 3 values between 0-255.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Rgb {
@@ -23,25 +143,228 @@ pub struct Rgb {
 
 impl Rgb {
     #[must_use]
-    pub fn new(red: u8, green: u8, blue: u8) -> Self {
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
         Self { red, green, blue }
     }
 }
 
+impl Rgb {
+    /// Returns a lighter version of the color. The amount is a fraction between
+    /// 0.0-1.0 describing how far the perceived lightness should move towards
+    /// white, where 1.0 always results in white.
+    #[must_use]
+    pub fn lighten(self, amount: f32) -> Self {
+        let mut color = Oklch::from(self);
+        color.lightness += (1.0 - color.lightness) * amount.clamp(0.0, 1.0);
+
+        gamut_map(color)
+    }
+
+    /// Returns a darker version of the color. The amount is a fraction between
+    /// 0.0-1.0 describing how far the perceived lightness should move towards
+    /// black, where 1.0 always results in black.
+    #[must_use]
+    pub fn darken(self, amount: f32) -> Self {
+        let mut color = Oklch::from(self);
+        color.lightness -= color.lightness * amount.clamp(0.0, 1.0);
+
+        gamut_map(color)
+    }
+
+    /// Returns a more saturated version of the color, increasing the chroma by
+    /// the given fraction (e.g. 0.25 for 25% more colorful). The result is
+    /// kept inside the sRGB gamut without shifting the hue.
+    #[must_use]
+    pub fn saturate(self, amount: f32) -> Self {
+        let mut color = Oklch::from(self);
+        color.chroma *= 1.0 + amount.max(0.0);
+
+        gamut_map(color)
+    }
+
+    /// Returns a less saturated version of the color, reducing the chroma by
+    /// the given fraction between 0.0-1.0, where 1.0 results in a gray of the
+    /// same perceived lightness.
+    #[must_use]
+    pub fn desaturate(self, amount: f32) -> Self {
+        let mut color = Oklch::from(self);
+        color.chroma *= 1.0 - amount.clamp(0.0, 1.0);
+
+        gamut_map(color)
+    }
+
+    /// Blends the color with another color, where `t` is a fraction between
+    /// 0.0 (only this color) and 1.0 (only the other color). The blend happens
+    /// in the Oklab color space, so the midpoint looks perceptually halfway.
+    #[must_use]
+    pub fn mix(self, other: Rgb, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let from = Oklab::from(self);
+        let to = Oklab::from(other);
+
+        Rgb::from(Oklab::new(
+            from.lightness + (to.lightness - from.lightness) * t,
+            from.a + (to.a - from.a) * t,
+            from.b + (to.b - from.b) * t,
+        ))
+    }
+
+    /// Returns the complementary color, which has the opposite hue and the
+    /// same perceived lightness.
+    #[must_use]
+    pub fn complement(self) -> Self {
+        let mut color = Oklch::from(self);
+        color.hue = normalize_hue(color.hue + 180.0);
+
+        gamut_map(color)
+    }
+
+    /// Returns the color with each channel inverted, e.g. white becomes black.
+    #[must_use]
+    pub fn invert(self) -> Self {
+        Self::new(255 - self.red, 255 - self.green, 255 - self.blue)
+    }
+
+    /// Returns a gray with the same perceived lightness as the color.
+    #[must_use]
+    pub fn grayscale(self) -> Self {
+        let mut color = Oklch::from(self);
+        color.chroma = 0.0;
+
+        Rgb::from(color)
+    }
+
+    /// Returns the color as it would appear when drawn with the given opacity
+    /// (between 0.0-1.0) on top of a background color.
+    #[must_use]
+    pub fn with_alpha_over(self, background: Rgb, alpha: f32) -> Self {
+        background.mix(self, alpha)
+    }
+}
+
+impl From<Simple> for Rgb {
+    /// Approximates a `Simple` color using the default xterm palette. The base
+    /// color constants (`BLACK` through `WHITE`) map onto the first 8 palette
+    /// entries, just like they do when rendered.
+    fn from(color: Simple) -> Self {
+        match color.0 {
+            code @ BLACK..=WHITE => XTERM_BASE_COLORS[usize::from(code - BLACK)],
+            index => xterm_rgb(index),
+        }
+    }
+}
+
+/// Returns the color at an index of the default xterm 256 color palette.
+pub(crate) fn xterm_rgb(index: u8) -> Rgb {
+    match index {
+        0..=15 => XTERM_BASE_COLORS[usize::from(index)],
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+
+            Rgb::new(level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        index => {
+            let gray = 8 + (index - 232) * 10;
+
+            Rgb::new(gray, gray, gray)
+        }
+    }
+}
+
 impl From<&[u8; 3]> for Rgb {
     fn from(color: &[u8; 3]) -> Self {
         Self::new(color[0], color[1], color[2])
     }
 }
 
+/// Represents an error found while parsing a hexadecimal color.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseColorError {
+    /// The color did not have exactly 6 digits, not counting a leading `#`.
+    InvalidLength(usize),
+    /// The color contained a character which is not a hexadecimal digit.
+    InvalidDigit(char),
+}
+
+impl Display for ParseColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseColorError::InvalidLength(length) => {
+                write!(f, "expected 6 hexadecimal digits, found {length}")
+            }
+            ParseColorError::InvalidDigit(digit) => {
+                write!(f, "invalid hexadecimal digit `{digit}`")
+            }
+        }
+    }
+}
+
+impl Error for ParseColorError {}
+
+impl Rgb {
+    /// Parses a hexadecimal color such as `"#ff8800"` or `"ff8800"`.
+    pub fn from_hex(color: &str) -> Result<Rgb, ParseColorError> {
+        let digits = color.strip_prefix('#').unwrap_or(color);
+
+        if let Some(digit) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(ParseColorError::InvalidDigit(digit));
+        }
+
+        Rgb::parse_hex(digits).ok_or(ParseColorError::InvalidLength(digits.len()))
+    }
+
+    /// Returns the color as a lowercase hexadecimal string, such as
+    /// `"#ff8800"`.
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+
+    /// Parses a hexadecimal color such as `"#ff8800"` at compile time, which
+    /// allows colors to be declared as `const` items. The leading `#` is
+    /// optional.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the color is not exactly 6 hexadecimal digits. When used to
+    /// initialize a `const`, this is reported as a compile error instead.
+    #[must_use]
+    pub const fn from_hex_const(color: &str) -> Rgb {
+        match Rgb::parse_hex(color) {
+            Some(color) => color,
+            None => panic!("invalid hex color, expected 6 hexadecimal digits"),
+        }
+    }
+
+    /// Parses a hexadecimal color such as `"#ff8800"` or `"ff8800"`, returning
+    /// `None` if it is not exactly 6 hexadecimal digits.
+    pub(crate) const fn parse_hex(color: &str) -> Option<Rgb> {
+        match parse_hex(color) {
+            Some((red, green, blue)) => Some(Rgb::new(red, green, blue)),
+            None => None,
+        }
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = ParseColorError;
+
+    fn from_str(color: &str) -> Result<Self, Self::Err> {
+        Rgb::from_hex(color)
+    }
+}
+
+/// # Panics
+///
+/// Panics if the color is not a valid hexadecimal color. Use `Rgb::from_hex`
+/// or `str::parse` to handle invalid colors instead.
 impl From<&str> for Rgb {
     fn from(color: &str) -> Self {
-        let color = color.trim_start_matches('#');
-        let red = u8::from_str_radix(&color[0..2], 16).unwrap();
-        let green = u8::from_str_radix(&color[2..4], 16).unwrap();
-        let blue = u8::from_str_radix(&color[4..6], 16).unwrap();
-
-        Self::new(red, green, blue)
+        match Rgb::from_hex(color) {
+            Ok(color) => color,
+            Err(error) => panic!("invalid hex color `{color}`: {error}"),
+        }
     }
 }
 
@@ -60,4 +383,41 @@ impl Rgb {
             )
         }
     }
+
+    /// Returns an Rgb struct with 3 random values for use with 24-bit ANSI
+    /// escape sequences, drawn from the given random number generator. Use a
+    /// seeded generator for reproducible colors.
+    ///
+    /// Requires the `random` feature to be enabled.
+    #[cfg(feature = "random")]
+    pub fn random_with<R: rand::Rng + ?Sized>(rng: &mut R) -> Rgb {
+        Rgb::new(rng.gen::<u8>(), rng.gen::<u8>(), rng.gen::<u8>())
+    }
+}
+
+/// Parses a color in the form reported by terminals, such as
+/// `rgb:ffff/8888/0000`. Each channel has 1 to 4 hexadecimal digits, which are
+/// scaled to 8 bits.
+#[cfg(any(feature = "schemes", all(unix, feature = "query")))]
+pub(crate) fn parse_color_reply(color: &str) -> Option<Rgb> {
+    let channels = color
+        .strip_prefix("rgb:")
+        .or_else(|| color.strip_prefix("rgba:"))?;
+
+    let mut channels = channels.split('/').map(|channel| {
+        if channel.is_empty() || channel.len() > 4 {
+            return None;
+        }
+
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        let max = (1u32 << (4 * channel.len())) - 1;
+
+        u8::try_from((value * 255 + max / 2) / max).ok()
+    });
+
+    Some(Rgb::new(
+        channels.next()??,
+        channels.next()??,
+        channels.next()??,
+    ))
 }
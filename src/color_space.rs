@@ -0,0 +1,595 @@
+use crate::{background::BackgroundColor, color::Rgb, foreground::ForegroundColor};
+
+/// Represents a color in the HSL (hue-saturation-lightness) color space.
+///
+/// The hue is measured in degrees between 0-360, while the saturation and
+/// lightness are fractions between 0.0-1.0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsl {
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+}
+
+impl Hsl {
+    #[must_use]
+    pub const fn new(hue: f32, saturation: f32, lightness: f32) -> Self {
+        Self {
+            hue,
+            saturation,
+            lightness,
+        }
+    }
+}
+
+/// Represents a color in the HSV (hue-saturation-value) color space.
+///
+/// The hue is measured in degrees between 0-360, while the saturation and
+/// value are fractions between 0.0-1.0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsv {
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+impl Hsv {
+    #[must_use]
+    pub const fn new(hue: f32, saturation: f32, value: f32) -> Self {
+        Self {
+            hue,
+            saturation,
+            value,
+        }
+    }
+}
+
+/// Represents a color in the HWB (hue-whiteness-blackness) color space.
+///
+/// The hue is measured in degrees between 0-360, while the whiteness and
+/// blackness are fractions between 0.0-1.0. When the whiteness and blackness
+/// add up to more than 1.0, they are scaled down proportionally, as described
+/// in the CSS Color specification.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hwb {
+    pub hue: f32,
+    pub whiteness: f32,
+    pub blackness: f32,
+}
+
+impl Hwb {
+    #[must_use]
+    pub const fn new(hue: f32, whiteness: f32, blackness: f32) -> Self {
+        Self {
+            hue,
+            whiteness,
+            blackness,
+        }
+    }
+}
+
+/// Represents a color in the CIE L\*a\*b\* color space, relative to the D65
+/// white point used by sRGB.
+///
+/// The lightness is between 0.0-100.0, while `a` (green-red) and `b`
+/// (blue-yellow) are unbounded, but typically fall between -128.0-128.0 for
+/// colors that can be displayed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lab {
+    pub lightness: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl Lab {
+    #[must_use]
+    pub const fn new(lightness: f32, a: f32, b: f32) -> Self {
+        Self { lightness, a, b }
+    }
+}
+
+/// Represents a color in the CIE LCh color space, which is the cylindrical
+/// form of `Lab`.
+///
+/// The lightness is between 0.0-100.0, the chroma starts at 0.0 and the hue
+/// is measured in degrees between 0-360.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lch {
+    pub lightness: f32,
+    pub chroma: f32,
+    pub hue: f32,
+}
+
+impl Lch {
+    #[must_use]
+    pub const fn new(lightness: f32, chroma: f32, hue: f32) -> Self {
+        Self {
+            lightness,
+            chroma,
+            hue,
+        }
+    }
+}
+
+/// Represents a color in the Oklab color space, a perceptually uniform space
+/// well suited for blending colors and building lightness ramps.
+///
+/// The lightness is between 0.0-1.0, while `a` (green-red) and `b`
+/// (blue-yellow) typically fall between -0.4-0.4.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklab {
+    pub lightness: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl Oklab {
+    #[must_use]
+    pub const fn new(lightness: f32, a: f32, b: f32) -> Self {
+        Self { lightness, a, b }
+    }
+}
+
+/// Represents a color in the Oklch color space, which is the cylindrical form
+/// of `Oklab`. Rotating the hue keeps the perceived lightness unchanged.
+///
+/// The lightness is between 0.0-1.0, the chroma starts at 0.0 (typically
+/// below 0.4) and the hue is measured in degrees between 0-360.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklch {
+    pub lightness: f32,
+    pub chroma: f32,
+    pub hue: f32,
+}
+
+impl Oklch {
+    #[must_use]
+    pub const fn new(lightness: f32, chroma: f32, hue: f32) -> Self {
+        Self {
+            lightness,
+            chroma,
+            hue,
+        }
+    }
+}
+
+/// Converts a channel value between 0.0-1.0 into a value between 0-255.
+pub(crate) fn to_channel(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Wraps a hue into the 0-360 degree range.
+pub(crate) fn normalize_hue(hue: f32) -> f32 {
+    let hue = hue.rem_euclid(360.0);
+
+    // `rem_euclid` can round up to exactly 360.0 for tiny negative values.
+    if hue >= 360.0 {
+        0.0
+    } else {
+        hue
+    }
+}
+
+/// Returns the hue, the largest channel and the smallest channel of an `Rgb`
+/// color, with the channels scaled to 0.0-1.0.
+fn hue_and_range(color: Rgb) -> (f32, f32, f32) {
+    let red = f32::from(color.red) / 255.0;
+    let green = f32::from(color.green) / 255.0;
+    let blue = f32::from(color.blue) / 255.0;
+
+    let max = red.max(green).max(blue);
+    let min = red.min(green).min(blue);
+    let delta = max - min;
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == red {
+        60.0 * ((green - blue) / delta)
+    } else if max == green {
+        60.0 * ((blue - red) / delta + 2.0)
+    } else {
+        60.0 * ((red - green) / delta + 4.0)
+    };
+
+    (normalize_hue(hue), max, min)
+}
+
+/// Converts a hue, chroma and offset into an `Rgb` color.
+fn from_hue_chroma(hue: f32, chroma: f32, offset: f32) -> Rgb {
+    let sector = normalize_hue(hue) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    let (red, green, blue) = match sector as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    Rgb::new(
+        to_channel(red + offset),
+        to_channel(green + offset),
+        to_channel(blue + offset),
+    )
+}
+
+impl From<Rgb> for Hsl {
+    fn from(color: Rgb) -> Self {
+        let (hue, max, min) = hue_and_range(color);
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+
+        Self::new(hue, saturation, lightness)
+    }
+}
+
+impl From<Hsl> for Rgb {
+    fn from(color: Hsl) -> Self {
+        let saturation = color.saturation.clamp(0.0, 1.0);
+        let lightness = color.lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+
+        from_hue_chroma(color.hue, chroma, lightness - chroma / 2.0)
+    }
+}
+
+impl From<Rgb> for Hsv {
+    fn from(color: Rgb) -> Self {
+        let (hue, max, min) = hue_and_range(color);
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+
+        Self::new(hue, saturation, max)
+    }
+}
+
+impl From<Hsv> for Rgb {
+    fn from(color: Hsv) -> Self {
+        let saturation = color.saturation.clamp(0.0, 1.0);
+        let value = color.value.clamp(0.0, 1.0);
+        let chroma = value * saturation;
+
+        from_hue_chroma(color.hue, chroma, value - chroma)
+    }
+}
+
+impl From<Rgb> for Hwb {
+    fn from(color: Rgb) -> Self {
+        let (hue, max, min) = hue_and_range(color);
+
+        Self::new(hue, min, 1.0 - max)
+    }
+}
+
+impl From<Hwb> for Rgb {
+    fn from(color: Hwb) -> Self {
+        let mut whiteness = color.whiteness.clamp(0.0, 1.0);
+        let mut blackness = color.blackness.clamp(0.0, 1.0);
+
+        let total = whiteness + blackness;
+        if total >= 1.0 {
+            whiteness /= total;
+            blackness /= total;
+        }
+
+        let value = 1.0 - blackness;
+        let saturation = if value == 0.0 {
+            0.0
+        } else {
+            1.0 - whiteness / value
+        };
+
+        Rgb::from(Hsv::new(color.hue, saturation, value))
+    }
+}
+
+impl From<Hsl> for Hsv {
+    fn from(color: Hsl) -> Self {
+        let value = color.lightness + color.saturation * color.lightness.min(1.0 - color.lightness);
+        let saturation = if value == 0.0 {
+            0.0
+        } else {
+            2.0 * (1.0 - color.lightness / value)
+        };
+
+        Self::new(color.hue, saturation, value)
+    }
+}
+
+impl From<Hsv> for Hsl {
+    fn from(color: Hsv) -> Self {
+        let lightness = color.value * (1.0 - color.saturation / 2.0);
+        let saturation = if lightness == 0.0 || lightness == 1.0 {
+            0.0
+        } else {
+            (color.value - lightness) / lightness.min(1.0 - lightness)
+        };
+
+        Self::new(color.hue, saturation, lightness)
+    }
+}
+
+/// Converts an sRGB channel value into linear light, between 0.0-1.0.
+pub(crate) fn to_linear(channel: u8) -> f64 {
+    let value = f64::from(channel) / 255.0;
+
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear light value back into a gamma-encoded sRGB channel.
+pub(crate) fn from_linear(value: f64) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let encoded = if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+
+    (encoded * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// Returns the linear red, green and blue light of an `Rgb` color.
+pub(crate) fn linear_rgb(color: Rgb) -> [f64; 3] {
+    [
+        to_linear(color.red),
+        to_linear(color.green),
+        to_linear(color.blue),
+    ]
+}
+
+/// Builds an `Rgb` color from linear red, green and blue light, clamping any
+/// values that fall outside of the sRGB gamut.
+pub(crate) fn from_linear_rgb([red, green, blue]: [f64; 3]) -> Rgb {
+    Rgb::new(from_linear(red), from_linear(green), from_linear(blue))
+}
+
+// The D65 reference white, as used by sRGB.
+const WHITE_X: f64 = 0.950_47;
+const WHITE_Y: f64 = 1.0;
+const WHITE_Z: f64 = 1.088_83;
+
+// CIE constants for the L*a*b* transfer function: (6 / 29)^3 and (29 / 6)^2 / 3.
+const LAB_EPSILON: f64 = 216.0 / 24389.0;
+const LAB_KAPPA: f64 = 24389.0 / 27.0;
+
+fn lab_forward(value: f64) -> f64 {
+    if value > LAB_EPSILON {
+        value.cbrt()
+    } else {
+        (LAB_KAPPA * value + 16.0) / 116.0
+    }
+}
+
+fn lab_inverse(value: f64) -> f64 {
+    let cubed = value.powi(3);
+
+    if cubed > LAB_EPSILON {
+        cubed
+    } else {
+        (116.0 * value - 16.0) / LAB_KAPPA
+    }
+}
+
+impl From<Rgb> for Lab {
+    fn from(color: Rgb) -> Self {
+        let [red, green, blue] = linear_rgb(color);
+
+        let x = 0.412_456_4 * red + 0.357_576_1 * green + 0.180_437_5 * blue;
+        let y = 0.212_672_9 * red + 0.715_152_2 * green + 0.072_175_0 * blue;
+        let z = 0.019_333_9 * red + 0.119_192_0 * green + 0.950_304_1 * blue;
+
+        let fx = lab_forward(x / WHITE_X);
+        let fy = lab_forward(y / WHITE_Y);
+        let fz = lab_forward(z / WHITE_Z);
+
+        Self::new(
+            (116.0 * fy - 16.0) as f32,
+            (500.0 * (fx - fy)) as f32,
+            (200.0 * (fy - fz)) as f32,
+        )
+    }
+}
+
+impl From<Lab> for Rgb {
+    fn from(color: Lab) -> Self {
+        let fy = (f64::from(color.lightness) + 16.0) / 116.0;
+        let fx = fy + f64::from(color.a) / 500.0;
+        let fz = fy - f64::from(color.b) / 200.0;
+
+        let x = lab_inverse(fx) * WHITE_X;
+        let y = lab_inverse(fy) * WHITE_Y;
+        let z = lab_inverse(fz) * WHITE_Z;
+
+        from_linear_rgb([
+            3.240_454_2 * x - 1.537_138_5 * y - 0.498_531_4 * z,
+            -0.969_266_0 * x + 1.876_010_8 * y + 0.041_556_0 * z,
+            0.055_643_4 * x - 0.204_025_9 * y + 1.057_225_2 * z,
+        ])
+    }
+}
+
+impl From<Lab> for Lch {
+    fn from(color: Lab) -> Self {
+        let (chroma, hue) = to_polar(color.a, color.b);
+
+        Self::new(color.lightness, chroma, hue)
+    }
+}
+
+impl From<Lch> for Lab {
+    fn from(color: Lch) -> Self {
+        let (a, b) = from_polar(color.chroma, color.hue);
+
+        Self::new(color.lightness, a, b)
+    }
+}
+
+impl From<Rgb> for Lch {
+    fn from(color: Rgb) -> Self {
+        Lch::from(Lab::from(color))
+    }
+}
+
+impl From<Lch> for Rgb {
+    fn from(color: Lch) -> Self {
+        Rgb::from(Lab::from(color))
+    }
+}
+
+impl From<Rgb> for Oklab {
+    fn from(color: Rgb) -> Self {
+        let [red, green, blue] = linear_rgb(color);
+
+        let l = (0.412_221_470_8 * red + 0.536_332_536_3 * green + 0.051_445_992_9 * blue).cbrt();
+        let m = (0.211_903_498_2 * red + 0.680_699_545_1 * green + 0.107_396_956_6 * blue).cbrt();
+        let s = (0.088_302_461_9 * red + 0.281_718_837_6 * green + 0.629_978_700_5 * blue).cbrt();
+
+        Self::new(
+            (0.210_454_255_3 * l + 0.793_617_785_0 * m - 0.004_072_046_8 * s) as f32,
+            (1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s) as f32,
+            (0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s) as f32,
+        )
+    }
+}
+
+/// Converts an `Oklab` color into linear red, green and blue light, without
+/// clamping to the sRGB gamut.
+fn oklab_to_linear(color: Oklab) -> [f64; 3] {
+    let lightness = f64::from(color.lightness);
+    let a = f64::from(color.a);
+    let b = f64::from(color.b);
+
+    let l = (lightness + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+    let m = (lightness - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+    let s = (lightness - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+
+    [
+        4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s,
+        -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s,
+        -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701_0 * s,
+    ]
+}
+
+impl From<Oklab> for Rgb {
+    fn from(color: Oklab) -> Self {
+        from_linear_rgb(oklab_to_linear(color))
+    }
+}
+
+/// Converts an `Oklch` color into the closest `Rgb` color with the same
+/// lightness and hue, reducing the chroma until the color fits inside the sRGB
+/// gamut. This avoids the hue shifts caused by clamping each channel.
+pub(crate) fn gamut_map(color: Oklch) -> Rgb {
+    const EPSILON: f64 = 1e-4;
+
+    // Pure black and white have no room for any chroma at all.
+    if color.lightness <= 0.0 {
+        return Rgb::new(0, 0, 0);
+    } else if color.lightness >= 1.0 {
+        return Rgb::new(255, 255, 255);
+    }
+
+    let lightness = color.lightness;
+    let fits = |chroma: f32| {
+        oklab_to_linear(Oklab::from(Oklch::new(lightness, chroma, color.hue)))
+            .iter()
+            .all(|channel| (-EPSILON..=1.0 + EPSILON).contains(channel))
+    };
+
+    let mut chroma = color.chroma.max(0.0);
+    if !fits(chroma) {
+        let (mut low, mut high) = (0.0, chroma);
+        for _ in 0..24 {
+            let middle = (low + high) / 2.0;
+            if fits(middle) {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        chroma = low;
+    }
+
+    Rgb::from(Oklch::new(lightness, chroma, color.hue))
+}
+
+impl From<Oklab> for Oklch {
+    fn from(color: Oklab) -> Self {
+        let (chroma, hue) = to_polar(color.a, color.b);
+
+        Self::new(color.lightness, chroma, hue)
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(color: Oklch) -> Self {
+        let (a, b) = from_polar(color.chroma, color.hue);
+
+        Self::new(color.lightness, a, b)
+    }
+}
+
+impl From<Rgb> for Oklch {
+    fn from(color: Rgb) -> Self {
+        Oklch::from(Oklab::from(color))
+    }
+}
+
+impl From<Oklch> for Rgb {
+    fn from(color: Oklch) -> Self {
+        Rgb::from(Oklab::from(color))
+    }
+}
+
+/// Converts the `a` and `b` axes of a Lab-like space into a chroma and a hue
+/// in degrees.
+fn to_polar(a: f32, b: f32) -> (f32, f32) {
+    let chroma = a.hypot(b);
+
+    // Achromatic colors have no meaningful hue; pin it to zero so that they
+    // compare equal regardless of floating point noise.
+    let hue = if chroma < 1e-4 {
+        0.0
+    } else {
+        normalize_hue(b.atan2(a).to_degrees())
+    };
+
+    (chroma, hue)
+}
+
+/// Converts a chroma and a hue in degrees back into `a` and `b` axes.
+fn from_polar(chroma: f32, hue: f32) -> (f32, f32) {
+    let (sin, cos) = hue.to_radians().sin_cos();
+
+    (chroma * cos, chroma * sin)
+}
+
+macro_rules! impl_into_color {
+    ($($space:ty),*) => {
+        $(
+            impl From<$space> for ForegroundColor {
+                fn from(color: $space) -> Self {
+                    ForegroundColor::Rgb(Rgb::from(color))
+                }
+            }
+
+            impl From<$space> for BackgroundColor {
+                fn from(color: $space) -> Self {
+                    BackgroundColor::Rgb(Rgb::from(color))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_color!(Hsl, Hsv, Hwb, Lab, Lch, Oklab, Oklch);
@@ -0,0 +1,141 @@
+use crate::{
+    color::Rgb,
+    color_space::{gamut_map, linear_rgb, Oklch},
+    foreground::ForegroundColor,
+    styled_string::StyledString,
+};
+
+/// Represents the minimum contrast levels for normal text defined by the Web
+/// Content Accessibility Guidelines (WCAG).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContrastLevel {
+    /// A contrast ratio of at least 4.5:1.
+    #[default]
+    Aa,
+    /// A contrast ratio of at least 7:1.
+    Aaa,
+}
+
+impl ContrastLevel {
+    /// Returns the minimum contrast ratio required by the level.
+    #[must_use]
+    pub fn ratio(&self) -> f32 {
+        match self {
+            ContrastLevel::Aa => 4.5,
+            ContrastLevel::Aaa => 7.0,
+        }
+    }
+}
+
+impl Rgb {
+    /// Returns the relative luminance of the color as defined by WCAG, between
+    /// 0.0 (black) and 1.0 (white).
+    #[must_use]
+    pub fn relative_luminance(&self) -> f32 {
+        let [red, green, blue] = linear_rgb(*self);
+
+        (0.2126 * red + 0.7152 * green + 0.0722 * blue) as f32
+    }
+
+    /// Returns the WCAG contrast ratio between two colors, between 1.0 (no
+    /// contrast) and 21.0 (black on white). The order of the colors does not
+    /// matter.
+    #[must_use]
+    pub fn contrast_ratio(&self, other: &Rgb) -> f32 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Returns a version of the color which has at least the given contrast
+    /// ratio against the background. The color is lightened or darkened as
+    /// little as possible while keeping its hue; if the ratio cannot be met,
+    /// the result is black or white, whichever contrasts more.
+    #[must_use]
+    pub fn ensure_contrast(self, background: &Rgb, ratio: f32) -> Self {
+        if self.contrast_ratio(background) >= ratio {
+            return self;
+        }
+
+        let black = Rgb::new(0, 0, 0);
+        let white = Rgb::new(255, 255, 255);
+        let (extreme, target) =
+            if white.contrast_ratio(background) >= black.contrast_ratio(background) {
+                (white, 1.0)
+            } else {
+                (black, 0.0)
+            };
+
+        if extreme.contrast_ratio(background) < ratio {
+            return extreme;
+        }
+
+        // Search for the smallest change in lightness that meets the ratio.
+        let color = Oklch::from(self);
+        let shifted = |t: f32| {
+            let lightness = color.lightness + (target - color.lightness) * t;
+            gamut_map(Oklch::new(lightness, color.chroma, color.hue))
+        };
+
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..20 {
+            let middle = (low + high) / 2.0;
+            if shifted(middle).contrast_ratio(background) >= ratio {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+
+        let result = shifted(high);
+        if result.contrast_ratio(background) >= ratio {
+            result
+        } else {
+            extreme
+        }
+    }
+}
+
+impl StyledString {
+    /// Makes sure the text is readable against its background color, meeting
+    /// the WCAG AA contrast ratio. See `auto_contrast_level` for details.
+    pub fn auto_contrast(self) -> Self {
+        self.auto_contrast_level(ContrastLevel::Aa)
+    }
+
+    /// Makes sure the text is readable against its background color, meeting
+    /// the given WCAG contrast level.
+    ///
+    /// If no foreground color is set, black or white is picked, whichever
+    /// contrasts more. Otherwise, the foreground color is lightened or darkened
+    /// just enough to meet the level. Strings without a background color are
+    /// returned unchanged.
+    pub fn auto_contrast_level(mut self, level: ContrastLevel) -> Self {
+        let Some(background) = self.background.to_rgb() else {
+            return self;
+        };
+
+        let foreground = match self.foreground.to_rgb() {
+            Some(color) => color.ensure_contrast(&background, level.ratio()),
+            None => {
+                let black = Rgb::new(0, 0, 0);
+                let white = Rgb::new(255, 255, 255);
+
+                if white.contrast_ratio(&background) >= black.contrast_ratio(&background) {
+                    white
+                } else {
+                    black
+                }
+            }
+        };
+
+        // Leave colors that already meet the level untouched, so that simple
+        // colors are not needlessly turned into RGB colors.
+        if self.foreground.to_rgb() != Some(foreground) {
+            self.foreground = ForegroundColor::Rgb(foreground);
+        }
+
+        self
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    color::Rgb,
+    color_space::{Lab, Oklab},
+};
+
+/// Represents the formulas available for measuring the perceived difference
+/// between two colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Metric {
+    /// The Euclidean distance in CIELAB (ΔE\*ab, 1976). Fast, but overstates
+    /// differences between saturated colors.
+    Cie76,
+    /// The CIE 1994 formula (ΔE\*94), using the graphic arts weights.
+    Cie94,
+    /// The CIEDE2000 formula (ΔE00), the most accurate of the CIE metrics. A
+    /// difference below 1.0 is generally not noticeable.
+    #[default]
+    Ciede2000,
+    /// The Euclidean distance in the Oklab color space, scaled by 100 so that
+    /// its range is comparable to the CIE metrics.
+    Oklab,
+}
+
+impl Rgb {
+    /// Returns the perceived difference between two colors using the given
+    /// metric, where 0.0 means the colors are identical.
+    #[must_use]
+    pub fn distance(&self, other: &Rgb, metric: Metric) -> f32 {
+        match metric {
+            Metric::Cie76 => cie76(Lab::from(*self), Lab::from(*other)),
+            Metric::Cie94 => cie94(Lab::from(*self), Lab::from(*other)),
+            Metric::Ciede2000 => ciede2000(Lab::from(*self), Lab::from(*other)),
+            Metric::Oklab => {
+                let a = Oklab::from(*self);
+                let b = Oklab::from(*other);
+
+                let dl = a.lightness - b.lightness;
+                let da = a.a - b.a;
+                let db = a.b - b.b;
+
+                (dl * dl + da * da + db * db).sqrt() * 100.0
+            }
+        }
+    }
+
+    /// Returns the index of the palette color closest to this color using the
+    /// given metric, or `None` if the palette is empty.
+    #[must_use]
+    pub fn closest(&self, palette: &[Rgb], metric: Metric) -> Option<usize> {
+        palette
+            .iter()
+            .map(|color| self.distance(color, metric))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
+}
+
+fn cie76(a: Lab, b: Lab) -> f32 {
+    let dl = a.lightness - b.lightness;
+    let da = a.a - b.a;
+    let db = a.b - b.b;
+
+    (dl * dl + da * da + db * db).sqrt()
+}
+
+fn cie94(a: Lab, b: Lab) -> f32 {
+    const K1: f32 = 0.045;
+    const K2: f32 = 0.015;
+
+    let c1 = a.a.hypot(a.b);
+    let c2 = b.a.hypot(b.b);
+
+    let dl = a.lightness - b.lightness;
+    let dc = c1 - c2;
+    let da = a.a - b.a;
+    let db = a.b - b.b;
+
+    // The hue difference is derived from the others; rounding can push it
+    // slightly below zero for near-identical colors.
+    let dh_squared = (da * da + db * db - dc * dc).max(0.0);
+
+    let sc = 1.0 + K1 * c1;
+    let sh = 1.0 + K2 * c1;
+
+    (dl * dl + (dc / sc).powi(2) + dh_squared / (sh * sh)).sqrt()
+}
+
+pub(crate) fn ciede2000(a: Lab, b: Lab) -> f32 {
+    // See "The CIEDE2000 Color-Difference Formula: Implementation Notes,
+    // Supplementary Test Data, and Mathematical Observations" by Sharma, Wu
+    // and Dalal. Computed in f64, as the formula is sensitive to rounding.
+    let (l1, a1, b1) = (f64::from(a.lightness), f64::from(a.a), f64::from(a.b));
+    let (l2, a2, b2) = (f64::from(b.lightness), f64::from(b.a), f64::from(b.b));
+
+    let c_mean = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (c_mean.powi(7) / (c_mean.powi(7) + 25f64.powi(7))).sqrt());
+
+    let a1 = a1 * (1.0 + g);
+    let a2 = a2 * (1.0 + g);
+
+    let c1 = a1.hypot(b1);
+    let c2 = a2.hypot(b2);
+
+    let hue = |b: f64, a: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let h1 = hue(b1, a1);
+    let h2 = hue(b2, a2);
+
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+    let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_mean = (l1 + l2) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_mean - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_mean).to_radians().cos()
+        + 0.32 * (3.0 * h_mean + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_mean - 63.0).to_radians().cos();
+
+    let sl = 1.0 + (0.015 * (l_mean - 50.0).powi(2)) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * c_mean;
+    let sh = 1.0 + 0.015 * c_mean * t;
+
+    let d_theta = 30.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp();
+    let rc = 2.0 * (c_mean.powi(7) / (c_mean.powi(7) + 25f64.powi(7))).sqrt();
+    let rt = -(2.0 * d_theta).to_radians().sin() * rc;
+
+    let dl = dl / sl;
+    let dc = dc / sc;
+    let dh = dh / sh;
+
+    (dl * dl + dc * dc + dh * dh + rt * dc * dh).sqrt() as f32
+}
@@ -0,0 +1,201 @@
+use crate::{
+    background::BackgroundColor,
+    color::Rgb,
+    color_space::{normalize_hue, Hsl},
+    foreground::ForegroundColor,
+    styled_string::StyledString,
+    styled_text::StyledText,
+};
+
+/// Represents the color space in which the colors between two gradient stops
+/// are calculated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Blends in the Oklab color space, which gives even, perceptually smooth
+    /// transitions without muddy midpoints.
+    #[default]
+    Oklab,
+    /// Blends the hue, saturation and lightness, taking the shortest way
+    /// around the color wheel. Passes through vivid intermediate hues.
+    Hsl,
+    /// Blends each red, green and blue channel directly.
+    Srgb,
+}
+
+impl Interpolation {
+    /// Returns the color at position `t` (between 0.0-1.0) between two colors.
+    #[must_use]
+    pub fn blend(&self, from: Rgb, to: Rgb, t: f32) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Interpolation::Oklab => from.mix(to, t),
+            Interpolation::Hsl => {
+                let from = Hsl::from(from);
+                let to = Hsl::from(to);
+
+                // Gray colors have no meaningful hue, so borrow the other one
+                // to avoid sweeping through unrelated hues.
+                let from_hue = if from.saturation == 0.0 {
+                    to.hue
+                } else {
+                    from.hue
+                };
+                let to_hue = if to.saturation == 0.0 {
+                    from_hue
+                } else {
+                    to.hue
+                };
+
+                let mut delta = to_hue - from_hue;
+                if delta > 180.0 {
+                    delta -= 360.0;
+                } else if delta < -180.0 {
+                    delta += 360.0;
+                }
+
+                Rgb::from(Hsl::new(
+                    normalize_hue(from_hue + delta * t),
+                    from.saturation + (to.saturation - from.saturation) * t,
+                    from.lightness + (to.lightness - from.lightness) * t,
+                ))
+            }
+            Interpolation::Srgb => {
+                let channel = |from: u8, to: u8| {
+                    (f32::from(from) + (f32::from(to) - f32::from(from)) * t).round() as u8
+                };
+
+                Rgb::new(
+                    channel(from.red, to.red),
+                    channel(from.green, to.green),
+                    channel(from.blue, to.blue),
+                )
+            }
+        }
+    }
+
+    /// Returns the color at position `t` (between 0.0-1.0) along a gradient
+    /// of evenly spaced color stops, or `None` if there are no stops.
+    #[must_use]
+    pub fn sample(&self, stops: &[Rgb], t: f32) -> Option<Rgb> {
+        match stops {
+            [] => None,
+            [color] => Some(*color),
+            _ => {
+                let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+                let index = (position.floor() as usize).min(stops.len() - 2);
+
+                Some(self.blend(stops[index], stops[index + 1], position - index as f32))
+            }
+        }
+    }
+}
+
+/// Splits text into user-perceived characters, so that combining marks,
+/// emoji sequences and flags receive a single color.
+///
+/// This is an approximation of Unicode extended grapheme clusters, which
+/// covers the common cases without pulling in the full segmentation tables.
+pub(crate) fn graphemes(text: &str) -> Vec<&str> {
+    fn is_extending(c: char) -> bool {
+        matches!(c,
+            '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{200C}'..='\u{200D}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FE20}'..='\u{FE2F}'
+            | '\u{1F3FB}'..='\u{1F3FF}'
+            | '\u{E0020}'..='\u{E007F}'
+            | '\u{E0100}'..='\u{E01EF}')
+    }
+
+    fn is_regional_indicator(c: char) -> bool {
+        ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+    }
+
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut previous: Option<char> = None;
+    let mut regional_indicators = 0;
+
+    for (index, c) in text.char_indices() {
+        let joins = match previous {
+            None => false,
+            Some('\u{200D}') => true,
+            Some('\r') => c == '\n',
+            Some(_) if is_extending(c) => true,
+            Some(previous) => {
+                is_regional_indicator(previous)
+                    && is_regional_indicator(c)
+                    && regional_indicators % 2 == 1
+            }
+        };
+
+        if !joins && index > 0 {
+            clusters.push(&text[start..index]);
+            start = index;
+        }
+
+        regional_indicators = if is_regional_indicator(c) {
+            regional_indicators + 1
+        } else {
+            0
+        };
+        previous = Some(c);
+    }
+
+    if start < text.len() {
+        clusters.push(&text[start..]);
+    }
+
+    clusters
+}
+
+impl StyledString {
+    /// Colors each character of the text along a gradient of evenly spaced
+    /// color stops. Any modes and the background color are kept on every
+    /// character.
+    pub fn gradient(self, stops: &[Rgb], interpolation: Interpolation) -> StyledText {
+        self.split_colored(|span, t| {
+            if let Some(color) = interpolation.sample(stops, t) {
+                span.foreground = ForegroundColor::Rgb(color);
+            }
+        })
+    }
+
+    /// Colors the background behind each character of the text along a
+    /// gradient of evenly spaced color stops. Any modes and the foreground
+    /// color are kept on every character.
+    pub fn background_gradient(self, stops: &[Rgb], interpolation: Interpolation) -> StyledText {
+        self.split_colored(|span, t| {
+            if let Some(color) = interpolation.sample(stops, t) {
+                span.background = BackgroundColor::Rgb(color);
+            }
+        })
+    }
+
+    /// Splits the string into one span per character, calling `apply` with
+    /// each span and its position (between 0.0-1.0) within the text.
+    pub(crate) fn split_colored(self, mut apply: impl FnMut(&mut StyledString, f32)) -> StyledText {
+        let clusters = graphemes(&self.text);
+        let last = clusters.len().saturating_sub(1).max(1) as f32;
+
+        clusters
+            .into_iter()
+            .enumerate()
+            .map(|(index, cluster)| {
+                let mut span = StyledString {
+                    text: cluster.to_string(),
+                    modes: self.modes.clone(),
+                    foreground: self.foreground,
+                    background: self.background,
+                };
+                apply(&mut span, index as f32 / last);
+
+                span
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    color::Rgb,
+    color_space::{gamut_map, normalize_hue, Oklch},
+};
+
+/// The steps of a `Scale`, from the lightest to the darkest color.
+pub const SCALE_STEPS: [u16; 10] = [50, 100, 200, 300, 400, 500, 600, 700, 800, 900];
+
+// The Oklch lightness of each scale step, and how much of the seed's chroma is
+// kept, since very light and very dark colors cannot hold much chroma.
+const SCALE_LIGHTNESS: [f32; 10] = [0.97, 0.93, 0.87, 0.79, 0.70, 0.62, 0.53, 0.45, 0.37, 0.29];
+const SCALE_CHROMA: [f32; 10] = [0.15, 0.3, 0.55, 0.8, 0.95, 1.0, 0.95, 0.85, 0.7, 0.55];
+
+/// Represents a ramp of 10 tints and shades of a single hue, using the
+/// familiar 50-900 naming (e.g. `scale.get(500)`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Scale {
+    colors: [Rgb; 10],
+}
+
+impl Scale {
+    /// Returns the color at the given step (one of `SCALE_STEPS`), or `None` if
+    /// the step does not exist.
+    #[must_use]
+    pub fn get(&self, step: u16) -> Option<Rgb> {
+        SCALE_STEPS
+            .iter()
+            .position(|s| *s == step)
+            .map(|index| self.colors[index])
+    }
+
+    /// Returns every color in the scale, from the lightest to the darkest.
+    #[must_use]
+    pub fn colors(&self) -> [Rgb; 10] {
+        self.colors
+    }
+
+    /// Returns an iterator over each step and its color, from the lightest to
+    /// the darkest.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, Rgb)> + '_ {
+        SCALE_STEPS.into_iter().zip(self.colors)
+    }
+}
+
+impl Rgb {
+    /// Returns the color with its hue rotated by the given number of degrees,
+    /// keeping the perceived lightness and chroma.
+    #[must_use]
+    pub fn rotate_hue(self, degrees: f32) -> Self {
+        let mut color = Oklch::from(self);
+        color.hue = normalize_hue(color.hue + degrees);
+
+        gamut_map(color)
+    }
+
+    /// Returns the color and its complement, which sits opposite on the color
+    /// wheel.
+    #[must_use]
+    pub fn complementary(self) -> [Rgb; 2] {
+        [self, self.complement()]
+    }
+
+    /// Returns the color surrounded by its two neighbours on the color wheel,
+    /// 30 degrees to either side.
+    #[must_use]
+    pub fn analogous(self) -> [Rgb; 3] {
+        [self.rotate_hue(-30.0), self, self.rotate_hue(30.0)]
+    }
+
+    /// Returns the color and two others, evenly spaced 120 degrees apart on the
+    /// color wheel.
+    #[must_use]
+    pub fn triadic(self) -> [Rgb; 3] {
+        [self, self.rotate_hue(120.0), self.rotate_hue(240.0)]
+    }
+
+    /// Returns the color and three others, evenly spaced 90 degrees apart on
+    /// the color wheel.
+    #[must_use]
+    pub fn tetradic(self) -> [Rgb; 4] {
+        [
+            self,
+            self.rotate_hue(90.0),
+            self.rotate_hue(180.0),
+            self.rotate_hue(270.0),
+        ]
+    }
+
+    /// Returns the color and the two neighbours of its complement, 150 and 210
+    /// degrees away on the color wheel.
+    #[must_use]
+    pub fn split_complementary(self) -> [Rgb; 3] {
+        [self, self.rotate_hue(150.0), self.rotate_hue(210.0)]
+    }
+
+    /// Returns `count` tints of the color, evenly stepping towards (but never
+    /// reaching) white, starting with the color itself.
+    #[must_use]
+    pub fn tints(self, count: usize) -> Vec<Rgb> {
+        (0..count)
+            .map(|step| self.lighten(step as f32 / count as f32))
+            .collect()
+    }
+
+    /// Returns `count` shades of the color, evenly stepping towards (but never
+    /// reaching) black, starting with the color itself.
+    #[must_use]
+    pub fn shades(self, count: usize) -> Vec<Rgb> {
+        (0..count)
+            .map(|step| self.darken(step as f32 / count as f32))
+            .collect()
+    }
+
+    /// Returns a 10 step scale (50-900) of the color's hue, with evenly spaced
+    /// perceived lightness, which is useful for building a full palette from a
+    /// single brand color.
+    #[must_use]
+    pub fn scale(self) -> Scale {
+        let seed = Oklch::from(self);
+        let colors = std::array::from_fn(|index| {
+            gamut_map(Oklch::new(
+                SCALE_LIGHTNESS[index],
+                seed.chroma * SCALE_CHROMA[index],
+                seed.hue,
+            ))
+        });
+
+        Scale { colors }
+    }
+}
@@ -0,0 +1,103 @@
+use std::hash::{Hash, Hasher};
+
+use crate::{color::Rgb, color_space::Oklch};
+
+/// A 64-bit FNV-1a hasher which produces the same output on every platform.
+///
+/// Integers are always hashed as little-endian bytes and `usize`/`isize` as
+/// 64-bit values, unlike the default hasher, whose output may change between
+/// Rust releases and whose input depends on the platform.
+pub(crate) struct StableHasher(u64);
+
+impl StableHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    pub(crate) fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        // FNV spreads similar inputs (e.g. "web-1" and "web-2") poorly across
+        // the high bits, so finish with the SplitMix64 mixing function.
+        let mut hash = self.0;
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+        hash ^ (hash >> 31)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i16(&mut self, value: i16) {
+        self.write_u16(value as u16);
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32);
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i128(&mut self, value: i128) {
+        self.write_u128(value as u128);
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_u64(value as i64 as u64);
+    }
+}
+
+impl Rgb {
+    /// Returns a color derived from hashing the key, such as a hostname,
+    /// thread name or request ID. The same key always results in the same
+    /// color, across runs and on every platform.
+    ///
+    /// If the palette is empty, the hue is picked from the whole color wheel
+    /// while keeping the lightness and chroma fixed, so that every color is
+    /// equally readable on dark backgrounds. Otherwise, an entry from the
+    /// palette is picked.
+    #[must_use]
+    pub fn from_hash<K: Hash + ?Sized>(key: &K, palette: &[Rgb]) -> Rgb {
+        let mut hasher = StableHasher::new();
+        key.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        if palette.is_empty() {
+            let hue = (hash % 3600) as f32 / 10.0;
+
+            Rgb::from(Oklch::new(0.75, 0.12, hue))
+        } else {
+            palette[(hash % palette.len() as u64) as usize]
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use crate::{
+    background::BackgroundColor,
+    color::{xterm_rgb, Rgb},
+    constants::{BLACK, BLINK, BOLD, DIM, HIDDEN, INVERT, ITALIC, STRIKE, UNDERLINE, WHITE},
+    foreground::ForegroundColor,
+    palette::TerminalPalette,
+    styled_string::StyledString,
+    styled_text::StyledText,
+};
+
+/// How `Html` writes the styles of each span.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HtmlStyling {
+    /// Every span has a `style` attribute with its CSS properties.
+    #[default]
+    Inline,
+    /// Spans have class names, such as `ansi-bold` and `ansi-fg-1`, which
+    /// are defined by `Html::stylesheet`. Colors outside of the 16 color
+    /// palette still use a `style` attribute.
+    Classes,
+}
+
+/// Renders styled text as HTML `<span>` elements, using a `TerminalPalette`
+/// for the colors of `Simple` colors. The output is meant to be placed in a
+/// `<pre>` element, as whitespace is kept as is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Html {
+    palette: TerminalPalette,
+    styling: HtmlStyling,
+    prefix: String,
+}
+
+impl Default for Html {
+    fn default() -> Self {
+        Self {
+            palette: TerminalPalette::XTERM,
+            styling: HtmlStyling::Inline,
+            prefix: String::from("ansi-"),
+        }
+    }
+}
+
+impl Html {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the palette used for `Simple` colors, and for the default colors
+    /// of inverted text. The xterm palette is used by default.
+    #[must_use]
+    pub fn palette(mut self, palette: TerminalPalette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Sets whether styles are written as `style` attributes or class names.
+    #[must_use]
+    pub fn styling(mut self, styling: HtmlStyling) -> Self {
+        self.styling = styling;
+        self
+    }
+
+    /// Sets the prefix of the class names, which is `ansi-` by default. Only
+    /// ASCII letters, digits, `-` and `_` are kept, as the prefix is written
+    /// into both `class` attributes and the selectors of the stylesheet.
+    #[must_use]
+    pub fn class_prefix(mut self, prefix: impl AsRef<str>) -> Self {
+        self.prefix = prefix
+            .as_ref()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+            .collect();
+        self
+    }
+
+    /// Renders every span of the text, one after another.
+    #[must_use]
+    pub fn render(&self, text: &StyledText) -> String {
+        text.spans
+            .iter()
+            .map(|span| self.render_span(span))
+            .collect()
+    }
+
+    /// Renders a single span as a `<span>` element, or as plain escaped text
+    /// if it has no styles.
+    #[must_use]
+    pub fn render_span(&self, span: &StyledString) -> String {
+        let mut classes: Vec<String> = Vec::new();
+        let mut properties: Vec<String> = Vec::new();
+
+        let has_mode = |mode: u8| span.modes.contains(&mode);
+        let inline = self.styling == HtmlStyling::Inline;
+
+        let mut foreground = self.foreground(span.foreground.resolved());
+        let mut background = self.background(span.background.resolved());
+
+        // Inverted text needs real colors to swap, so the palette's default
+        // colors (or its white and black) stand in for empty ones.
+        if has_mode(INVERT) {
+            let default_foreground = self.palette.foreground.unwrap_or(self.palette.colors[7]);
+            let default_background = self.palette.background.unwrap_or(self.palette.colors[0]);
+
+            (foreground, background) = (
+                Some(background.unwrap_or(Color::Rgb(default_background))),
+                Some(foreground.unwrap_or(Color::Rgb(default_foreground))),
+            );
+        }
+
+        for (mode, name, property) in MODES {
+            if !has_mode(mode) {
+                continue;
+            }
+
+            if inline {
+                if let Some(property) = property {
+                    properties.push(property.to_string());
+                }
+            } else {
+                classes.push(format!("{}{name}", self.prefix));
+            }
+        }
+
+        if inline {
+            let decorations: Vec<&str> = [(UNDERLINE, "underline"), (STRIKE, "line-through")]
+                .into_iter()
+                .filter(|(mode, _)| has_mode(*mode))
+                .map(|(_, decoration)| decoration)
+                .collect();
+
+            if !decorations.is_empty() {
+                properties.push(format!("text-decoration: {}", decorations.join(" ")));
+            }
+        }
+
+        for (color, property, class) in [
+            (foreground, "color", "fg"),
+            (background, "background-color", "bg"),
+        ] {
+            match color {
+                Some(Color::Palette(index)) if !inline => {
+                    classes.push(format!("{}{class}-{index}", self.prefix));
+                }
+                Some(Color::Palette(index)) => {
+                    let hex = self.palette.colors[usize::from(index)].to_hex();
+                    properties.push(format!("{property}: {hex}"));
+                }
+                Some(Color::Rgb(rgb)) => properties.push(format!("{property}: {}", rgb.to_hex())),
+                None => {}
+            }
+        }
+
+        let text = escape(&span.text);
+        if classes.is_empty() && properties.is_empty() {
+            return text;
+        }
+
+        let mut attributes = String::new();
+        if !classes.is_empty() {
+            attributes.push_str(&format!(" class=\"{}\"", classes.join(" ")));
+        }
+        if !properties.is_empty() {
+            attributes.push_str(&format!(" style=\"{}\"", properties.join("; ")));
+        }
+
+        format!("<span{attributes}>{text}</span>")
+    }
+
+    /// Returns the CSS rules for the class names used with
+    /// `HtmlStyling::Classes`, with the 16 colors taken from the palette. If
+    /// the palette has default colors, they are set on the `terminal` class,
+    /// such as `ansi-terminal`, for the element containing the output.
+    #[must_use]
+    pub fn stylesheet(&self) -> String {
+        let prefix = &self.prefix;
+        let mut rules: Vec<String> = Vec::new();
+
+        let mut defaults = Vec::new();
+        if let Some(foreground) = self.palette.foreground {
+            defaults.push(format!("color: {}", foreground.to_hex()));
+        }
+        if let Some(background) = self.palette.background {
+            defaults.push(format!("background-color: {}", background.to_hex()));
+        }
+        if !defaults.is_empty() {
+            rules.push(format!(".{prefix}terminal {{ {}; }}", defaults.join("; ")));
+        }
+
+        for (_, name, property) in MODES {
+            if let Some(property) = property {
+                rules.push(format!(".{prefix}{name} {{ {property}; }}"));
+            }
+        }
+
+        rules.push(format!(
+            ".{prefix}underline {{ text-decoration: underline; }}"
+        ));
+        rules.push(format!(
+            ".{prefix}strike {{ text-decoration: line-through; }}"
+        ));
+        rules.push(format!(
+            ".{prefix}underline.{prefix}strike {{ text-decoration: underline line-through; }}"
+        ));
+
+        for (index, color) in self.palette.colors.iter().enumerate() {
+            rules.push(format!(
+                ".{prefix}fg-{index} {{ color: {}; }}",
+                color.to_hex()
+            ));
+        }
+        for (index, color) in self.palette.colors.iter().enumerate() {
+            rules.push(format!(
+                ".{prefix}bg-{index} {{ background-color: {}; }}",
+                color.to_hex()
+            ));
+        }
+
+        rules.join("\n") + "\n"
+    }
+
+    fn foreground(&self, color: ForegroundColor) -> Option<Color> {
+        match color {
+            ForegroundColor::Simple(color) => Some(self.simple(color)),
+            ForegroundColor::Indexed(index) => Some(indexed(index)),
+            ForegroundColor::Rgb(rgb) => Some(Color::Rgb(rgb)),
+            ForegroundColor::Empty | ForegroundColor::Adaptive(_) => None,
+        }
+    }
+
+    fn background(&self, color: BackgroundColor) -> Option<Color> {
+        match color {
+            BackgroundColor::Simple(color) => Some(self.simple(color)),
+            BackgroundColor::Indexed(index) => Some(indexed(index)),
+            BackgroundColor::Rgb(rgb) => Some(Color::Rgb(rgb)),
+            BackgroundColor::Empty | BackgroundColor::Adaptive(_) => None,
+        }
+    }
+
+    /// Maps a `Simple` color to its palette index, or its color outside of
+    /// the 16 palette colors.
+    fn simple(&self, color: u8) -> Color {
+        match color {
+            code @ BLACK..=WHITE => Color::Palette(code - BLACK),
+            index => indexed(index),
+        }
+    }
+}
+
+/// Maps an index of the 256 color palette to a palette color, or its fixed
+/// color outside of the 16 palette colors.
+fn indexed(index: u8) -> Color {
+    match index {
+        0..=15 => Color::Palette(index),
+        index => Color::Rgb(xterm_rgb(index)),
+    }
+}
+
+/// A color of a span, which is either one of the 16 palette colors or a
+/// fixed color.
+#[derive(Clone, Copy)]
+enum Color {
+    Palette(u8),
+    Rgb(Rgb),
+}
+
+/// The modes with class names, and their CSS properties. Underline and
+/// strike-through are combined into a single `text-decoration`, so they are
+/// handled separately, and blinking text is left as is.
+const MODES: [(u8, &str, Option<&str>); 7] = [
+    (BOLD, "bold", Some("font-weight: bold")),
+    (DIM, "dim", Some("opacity: 0.5")),
+    (ITALIC, "italic", Some("font-style: italic")),
+    (UNDERLINE, "underline", None),
+    (BLINK, "blink", None),
+    (HIDDEN, "hidden", Some("visibility: hidden")),
+    (STRIKE, "strike", None),
+];
+
+/// Escapes the characters with special meaning in HTML text and attributes.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            character => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+impl StyledString {
+    /// Renders the string as an HTML `<span>` with inline styles and the
+    /// xterm palette. See `Html` for other options.
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        Html::new().render_span(self)
+    }
+}
+
+impl StyledText {
+    /// Renders the text as HTML `<span>` elements with inline styles and the
+    /// xterm palette. See `Html` for other options.
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        Html::new().render(self)
+    }
+}
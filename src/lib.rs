@@ -1,23 +1,126 @@
 #![forbid(unsafe_code)]
 
+// Allows the `macros` feature to refer to this crate as `::iridescent`, even
+// from within the crate itself.
+extern crate self as iridescent;
+
+/// Colors that adapt to a light or dark terminal background.
+pub mod adaptive;
+/// Parsing of text with ANSI escape sequences, such as captured terminal
+/// output, into styled text.
+pub mod ansi;
 /// An enum representing a background color type.
 pub mod background;
 /// An enum representing different color types applied to a `StyledString`.
 pub mod color;
+/// Structs representing colors in the HSL, HSV, HWB, CIELAB, LCh, Oklab and
+/// Oklch color spaces, with conversions to and from `Rgb`.
+pub mod color_space;
 /// Various constants used by the library, including the base ANSI color values.
 pub mod constants;
+/// WCAG contrast calculations and automatic selection of readable foreground
+/// colors.
+pub mod contrast;
+/// Color difference metrics, such as CIEDE2000, for comparing `Rgb` colors.
+pub mod distance;
 /// An enum representing a foreground color type.
 pub mod foreground;
+/// Multi-stop color gradients applied across the characters of a string.
+pub mod gradient;
+/// Color harmonies (complementary, triadic, etc.) and tint and shade scales.
+pub mod harmony;
+/// Stable hashing of identifiers into colors.
+mod hash;
+/// Rendering of styled text as HTML, with inline styles or class names and a
+/// stylesheet.
+pub mod html;
+/// Lookup of file styles from the `LS_COLORS` environment variable and
+/// `dircolors` databases.
+pub mod ls_colors;
+/// A small markup language for styling text with tags, like `<red>error</red>`.
+pub mod markup;
+/// The 16 color palette of a terminal, and parsers for the color schemes of
+/// common terminal emulators.
+pub mod palette;
+/// Querying the colors of the terminal with OSC escape sequences.
+#[cfg(all(unix, feature = "query"))]
+pub mod query;
+/// Cycling rainbow effects, which can be animated frame by frame.
+pub mod rainbow;
+/// Random color generation with seeding and constraints, such as a minimum
+/// contrast against a background color.
+#[cfg(feature = "random")]
+pub mod random;
+/// Loading of base16 and base24 color schemes from YAML and JSON files.
+#[cfg(feature = "schemes")]
+pub mod scheme;
+/// `Serialize` and `Deserialize` implementations for colors and styles.
+#[cfg(feature = "serde")]
+mod serialize;
+/// Conversion between styles and SGR parameter strings.
+pub mod sgr;
+/// A struct representing a reusable set of modes and colors.
+pub mod style;
 /// Trait implementing various methods on `&str` and `String` types.
 pub mod styled;
 /// A struct representing the internal state of an `&str` or `String` type with
 /// applied styles.
 pub mod styled_string;
+/// A struct representing text made up of multiple `StyledString` spans.
+pub mod styled_text;
+/// Themes mapping semantic roles, such as errors and warnings, to styles.
+pub mod theme;
+/// The color settings of `grep`, GCC and git, read from `GREP_COLORS`,
+/// `GCC_COLORS` and git configuration files.
+pub mod tool_colors;
+/// Color vision deficiency simulation and daltonization.
+pub mod vision;
 
 pub use crate::{
-    background::*, color::*, constants::*, foreground::*, styled::*, styled_string::*,
+    adaptive::*, background::*, color::*, color_space::*, constants::*, contrast::*, distance::*,
+    foreground::*, gradient::*, harmony::*, html::*, ls_colors::*, markup::MarkupError, palette::*,
+    rainbow::*, style::*, styled::*, styled_string::*, styled_text::*, theme::*, tool_colors::*,
+    vision::*,
+};
+
+#[cfg(all(unix, feature = "query"))]
+pub use crate::query::*;
+#[cfg(feature = "random")]
+pub use crate::random::*;
+#[cfg(feature = "schemes")]
+pub use crate::scheme::*;
+#[cfg(feature = "macros")]
+pub use iridescent_macros::{
+    ceprint, ceprintln, cformat, cprint, cprintln, cwrite, cwriteln, rgb, style,
 };
 
+/// The macros reject invalid input at compile time, while the same input
+/// written correctly compiles:
+///
+/// ```
+/// let orange = iridescent::rgb!("#ff8800");
+/// let warning = iridescent::style!("bold yellow");
+/// let message = iridescent::cformat!("{red}x{/}");
+/// ```
+///
+/// ```compile_fail
+/// let orange = iridescent::rgb!("#ggg");
+/// ```
+///
+/// ```compile_fail
+/// let warning = iridescent::style!("bold nope");
+/// ```
+///
+/// ```compile_fail
+/// let message = iridescent::cformat!("{bold nope}x");
+/// ```
+///
+/// ```compile_fail
+/// let message = iridescent::cformat!("{red}x");
+/// ```
+#[cfg(all(doctest, feature = "macros"))]
+struct InvalidMacroInput;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,6 +188,18 @@ mod tests {
         assert_eq!(strike.to_string(), "\x1b[9mstrike\x1b[0m");
     }
 
+    #[test]
+    fn test_unstyled_text() {
+        // A `StyledString` keeps its empty escape sequence and reset, while a
+        // `StyledText` writes its unstyled spans as plain text.
+        assert_eq!(StyledString::new("plain").to_string(), "\x1b[mplain\x1b[0m");
+
+        let mut text = StyledText::new();
+        text.push("plain ");
+        text.push("bold".bold());
+        assert_eq!(text.to_string(), "plain \x1b[1mbold\x1b[0m");
+    }
+
     #[test]
     fn test_high_bit_depth() {
         let pure_red = "pure red".foreground(Rgb::new(255, 0, 0));
@@ -132,4 +247,1528 @@ mod tests {
         assert_eq!(white.to_string(), "\x1b[38;2;255;255;255mwhite\x1b[0m");
         assert_eq!(black.to_string(), "\x1b[38;2;0;0;0mblack\x1b[0m");
     }
+
+    #[test]
+    fn test_hsl_hsv_hwb_conversions() {
+        let orange = Rgb::new(255, 136, 0);
+
+        let hsl = Hsl::from(orange);
+        assert!((hsl.hue - 32.0).abs() < 0.01);
+        assert!((hsl.saturation - 1.0).abs() < 0.001);
+        assert!((hsl.lightness - 0.5).abs() < 0.001);
+
+        let hsv = Hsv::from(orange);
+        assert!((hsv.saturation - 1.0).abs() < 0.001);
+        assert!((hsv.value - 1.0).abs() < 0.001);
+
+        let hwb = Hwb::from(Rgb::new(128, 64, 64));
+        assert!((hwb.whiteness - 64.0 / 255.0).abs() < 0.001);
+        assert!((hwb.blackness - 127.0 / 255.0).abs() < 0.001);
+
+        assert_eq!(Rgb::from(Hsl::new(120.0, 1.0, 0.5)), Rgb::new(0, 255, 0));
+        assert_eq!(Rgb::from(Hsv::new(240.0, 1.0, 1.0)), Rgb::new(0, 0, 255));
+        assert_eq!(Rgb::from(Hwb::new(0.0, 0.8, 0.8)), Rgb::new(128, 128, 128));
+    }
+
+    #[test]
+    fn test_color_space_round_trips() {
+        for red in (0..=255).step_by(15) {
+            for green in (0..=255).step_by(17) {
+                for blue in (0..=255).step_by(51) {
+                    let color = Rgb::new(red, green, blue);
+
+                    assert_eq!(Rgb::from(Hsl::from(color)), color);
+                    assert_eq!(Rgb::from(Hsv::from(color)), color);
+                    assert_eq!(Rgb::from(Hwb::from(color)), color);
+                    assert_eq!(Rgb::from(Hsv::from(Hsl::from(color))), color);
+                }
+            }
+        }
+
+        let hsl = "hsl".foreground(Hsl::new(0.0, 1.0, 0.5));
+        assert_eq!(hsl.to_string(), "\x1b[38;2;255;0;0mhsl\x1b[0m");
+    }
+
+    #[test]
+    fn test_perceptual_color_spaces() {
+        let white = Oklab::from(Rgb::new(255, 255, 255));
+        assert!((white.lightness - 1.0).abs() < 0.001);
+        assert!(white.a.abs() < 0.001 && white.b.abs() < 0.001);
+
+        let red = Oklch::from(Rgb::new(255, 0, 0));
+        assert!((red.lightness - 0.628).abs() < 0.001);
+        assert!((red.chroma - 0.2577).abs() < 0.001);
+        assert!((red.hue - 29.23).abs() < 0.05);
+
+        let lab = Lab::from(Rgb::new(255, 0, 0));
+        assert!((lab.lightness - 53.24).abs() < 0.01);
+        assert!((lab.a - 80.09).abs() < 0.05);
+        assert!((lab.b - 67.20).abs() < 0.05);
+
+        let lch = Lch::from(Rgb::new(128, 128, 128));
+        assert!(lch.chroma < 0.01);
+        assert_eq!(lch.hue, 0.0);
+
+        for red in (0..=255).step_by(15) {
+            for green in (0..=255).step_by(17) {
+                for blue in (0..=255).step_by(51) {
+                    let color = Rgb::new(red, green, blue);
+
+                    assert_eq!(Rgb::from(Lab::from(color)), color);
+                    assert_eq!(Rgb::from(Lch::from(color)), color);
+                    assert_eq!(Rgb::from(Oklab::from(color)), color);
+                    assert_eq!(Rgb::from(Oklch::from(color)), color);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_color_manipulation() {
+        let brand = Rgb::new(52, 101, 164);
+
+        assert_eq!(brand.lighten(1.0), Rgb::new(255, 255, 255));
+        assert_eq!(brand.darken(1.0), Rgb::new(0, 0, 0));
+        assert_eq!(brand.lighten(0.0), brand);
+        assert!(Oklch::from(brand.lighten(0.2)).lightness > Oklch::from(brand).lightness);
+        assert!(Oklch::from(brand.darken(0.2)).lightness < Oklch::from(brand).lightness);
+
+        let saturated = Oklch::from(brand.saturate(0.5));
+        assert!(saturated.chroma > Oklch::from(brand).chroma);
+        assert!((saturated.hue - Oklch::from(brand).hue).abs() < 1.0);
+
+        let gray = brand.desaturate(1.0);
+        assert_eq!(gray, brand.grayscale());
+        assert_eq!(gray.red, gray.green);
+        assert_eq!(gray.green, gray.blue);
+
+        let black = Rgb::new(0, 0, 0);
+        let white = Rgb::new(255, 255, 255);
+        assert_eq!(black.mix(white, 0.0), black);
+        assert_eq!(black.mix(white, 1.0), white);
+        assert_eq!(white.with_alpha_over(black, 1.0), white);
+        assert_eq!(white.with_alpha_over(black, 0.0), black);
+
+        assert_eq!(white.invert(), black);
+        let complement = Oklch::from(brand.complement());
+        assert!(
+            (complement.hue - color_space::normalize_hue(Oklch::from(brand).hue + 180.0)).abs()
+                < 2.0
+        );
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let black = Rgb::new(0, 0, 0);
+        let white = Rgb::new(255, 255, 255);
+
+        assert_eq!(black.relative_luminance(), 0.0);
+        assert!((white.relative_luminance() - 1.0).abs() < 0.0001);
+        assert!((black.contrast_ratio(&white) - 21.0).abs() < 0.001);
+        assert_eq!(white.contrast_ratio(&black), black.contrast_ratio(&white));
+        assert_eq!(white.contrast_ratio(&white), 1.0);
+
+        let gray = Rgb::new(118, 118, 118);
+        assert!((gray.contrast_ratio(&white) - 4.54).abs() < 0.01);
+
+        assert_eq!(Rgb::from(Simple(RED)), Rgb::new(205, 0, 0));
+        assert_eq!(Rgb::from(Simple(9)), Rgb::new(255, 0, 0));
+        assert_eq!(Rgb::from(Simple(196)), Rgb::new(255, 0, 0));
+        assert_eq!(Rgb::from(Simple(244)), Rgb::new(128, 128, 128));
+    }
+
+    #[test]
+    fn test_auto_contrast() {
+        let background = Rgb::new(250, 250, 210);
+
+        let picked = "text".background(background).auto_contrast();
+        assert_eq!(picked.foreground, ForegroundColor::Rgb(Rgb::new(0, 0, 0)));
+
+        let dark = "text".background(BLUE).auto_contrast();
+        assert_eq!(
+            dark.foreground,
+            ForegroundColor::Rgb(Rgb::new(255, 255, 255))
+        );
+
+        let adjusted = "text"
+            .foreground(Rgb::new(255, 255, 0))
+            .background(background)
+            .auto_contrast_level(ContrastLevel::Aaa);
+        let color = adjusted.foreground.to_rgb().unwrap();
+        assert!(color.contrast_ratio(&background) >= 7.0);
+        assert!(color.contrast_ratio(&background) < 7.5);
+
+        let readable = "text".foreground(BLACK).background(WHITE).auto_contrast();
+        assert_eq!(readable.foreground, ForegroundColor::Simple(BLACK));
+
+        let unchanged = "text".foreground(Rgb::new(255, 255, 0)).auto_contrast();
+        assert_eq!(
+            unchanged.foreground,
+            ForegroundColor::Rgb(Rgb::new(255, 255, 0))
+        );
+    }
+
+    #[test]
+    fn test_color_distance() {
+        // Reference pairs from Sharma, Wu and Dalal's CIEDE2000 test data.
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, -1.3802, -84.2814), (50.0, 0.0, -82.7485), 1.0),
+            ((50.0, 2.5, 0.0), (50.0, 0.0, -2.5), 4.3065),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            (
+                (60.2574, -34.0099, 36.2677),
+                (60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+            (
+                (90.8027, -2.0831, 1.441),
+                (91.1528, -1.6435, 0.0447),
+                1.4441,
+            ),
+        ];
+
+        for ((l1, a1, b1), (l2, a2, b2), expected) in pairs {
+            let difference = distance::ciede2000(Lab::new(l1, a1, b1), Lab::new(l2, a2, b2));
+            assert!(
+                (difference - expected).abs() < 0.001,
+                "{difference} != {expected}"
+            );
+        }
+
+        let red = Rgb::new(255, 0, 0);
+        let near_red = Rgb::new(250, 5, 5);
+        let blue = Rgb::new(0, 0, 255);
+
+        for metric in [
+            Metric::Cie76,
+            Metric::Cie94,
+            Metric::Ciede2000,
+            Metric::Oklab,
+        ] {
+            assert_eq!(red.distance(&red, metric), 0.0);
+            assert!(red.distance(&near_red, metric) < red.distance(&blue, metric));
+        }
+
+        let palette = [blue, Rgb::new(0, 255, 0), near_red];
+        assert_eq!(red.closest(&palette, Metric::Ciede2000), Some(2));
+        assert_eq!(red.closest(&[], Metric::Ciede2000), None);
+    }
+
+    #[test]
+    fn test_gradient() {
+        let stops = [Rgb::new(255, 0, 0), Rgb::new(0, 0, 255)];
+        let text = "abc".gradient(&stops);
+
+        assert_eq!(text.spans().len(), 3);
+        assert_eq!(text.plain_text(), "abc");
+        assert_eq!(text.spans()[0].foreground, ForegroundColor::Rgb(stops[0]));
+        assert_eq!(text.spans()[2].foreground, ForegroundColor::Rgb(stops[1]));
+        assert_eq!(
+            text.to_string(),
+            format!(
+                "\x1b[38;2;255;0;0ma\x1b[0m{}\x1b[38;2;0;0;255mc\x1b[0m",
+                text.spans()[1]
+            )
+        );
+
+        let srgb = StyledString::new("ab_").bold().background(BLACK).gradient(
+            &[Rgb::new(0, 0, 0), Rgb::new(200, 100, 0)],
+            Interpolation::Srgb,
+        );
+        assert_eq!(
+            srgb.spans()[1].foreground,
+            ForegroundColor::Rgb(Rgb::new(100, 50, 0))
+        );
+        assert_eq!(
+            srgb.spans()[1].to_string(),
+            "\x1b[1;38;2;100;50;0;40mb\x1b[0m"
+        );
+
+        let hsl = Interpolation::Hsl.blend(Rgb::new(255, 0, 0), Rgb::new(0, 0, 255), 0.5);
+        assert_eq!(hsl, Rgb::new(255, 0, 255));
+
+        let background = "ab".background_gradient(&stops);
+        assert_eq!(
+            background.spans()[1].background,
+            BackgroundColor::Rgb(stops[1])
+        );
+
+        let combined = "e\u{301}👍🏽🇳🇱🇩🇪\r\n".gradient(&stops);
+        let clusters: Vec<&str> = combined.spans().iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(clusters, ["e\u{301}", "👍🏽", "🇳🇱", "🇩🇪", "\r\n"]);
+
+        assert_eq!("".gradient(&stops).spans().len(), 0);
+        assert_eq!(
+            "ab".gradient(&[]).spans()[0].foreground,
+            ForegroundColor::Empty
+        );
+    }
+
+    #[test]
+    fn test_rainbow() {
+        let rainbow = Rainbow::new();
+        assert_eq!(rainbow.color_at(0), Rgb::new(128, 238, 18));
+
+        let text = "abcd".rainbow();
+        assert_eq!(text.spans().len(), 4);
+        assert_eq!(
+            text.spans()[0].foreground,
+            ForegroundColor::Rgb(rainbow.color_at(0))
+        );
+        assert_eq!(
+            text.spans()[3].foreground,
+            ForegroundColor::Rgb(rainbow.color_at(3))
+        );
+
+        let shifted = Rainbow::new().phase(1.0).frequency(1.0).spread(1.0);
+        assert_eq!(
+            shifted.color_at(0),
+            Rainbow::new().frequency(1.0).spread(1.0).color_at(1)
+        );
+
+        let mut frames = Rainbow::new().speed(0.5).frames("ab".bold());
+        let first = frames.next().unwrap();
+        let second = frames.next().unwrap();
+        assert_ne!(first, second);
+        assert_eq!(second, Rainbow::new().phase(0.5).paint("ab".bold()));
+        assert_eq!(
+            first.spans()[0].to_string(),
+            "\x1b[1;38;2;128;238;18ma\x1b[0m"
+        );
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn test_random_colors() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut a = StdRng::seed_from_u64(7);
+        let mut b = StdRng::seed_from_u64(7);
+        assert_eq!(Rgb::random_with(&mut a), Rgb::random_with(&mut b));
+        assert_eq!(Simple::random_with(&mut a), Simple::random_with(&mut b));
+
+        let black = Rgb::new(0, 0, 0);
+        let readable = RandomColor::new().min_contrast(black, 7.0);
+        for seed in 0..50 {
+            let color = readable.generate_seeded(seed);
+            assert!(color.contrast_ratio(&black) >= 7.0);
+            assert_eq!(color, readable.generate_seeded(seed));
+        }
+
+        let impossible = RandomColor::new()
+            .lightness(0.0..=0.1)
+            .min_contrast(black, 4.5);
+        assert!(impossible.generate_seeded(1).contrast_ratio(&black) >= 4.5);
+
+        let pastel = RandomColor::new()
+            .saturation(0.4..=0.6)
+            .lightness(0.7..=0.8);
+        let hsl = Hsl::from(pastel.generate_seeded(3));
+        assert!((0.39..=0.61).contains(&hsl.saturation));
+        assert!((0.69..=0.81).contains(&hsl.lightness));
+
+        let reds = RandomColor::new().hue(330.0..=30.0).saturation(1.0..=1.0);
+        let hues: Vec<f32> = (0..50)
+            .map(|seed| Hsl::from(reds.generate_seeded(seed)).hue)
+            .collect();
+        assert!(hues.iter().all(|hue| *hue >= 329.0 || *hue <= 31.0));
+        assert!(hues.iter().any(|hue| *hue > 180.0) && hues.iter().any(|hue| *hue < 180.0));
+
+        let colors = RandomColor::new().distinct_seeded(6, 11);
+        assert_eq!(colors.len(), 6);
+        assert_eq!(colors, RandomColor::new().distinct_seeded(6, 11));
+        for (i, a) in colors.iter().enumerate() {
+            for b in &colors[i + 1..] {
+                assert!(a.distance(b, Metric::Oklab) > 5.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_style() {
+        let style = Style::new()
+            .bold()
+            .underline()
+            .foreground(RED)
+            .background("#202020");
+
+        assert!(style.has_mode(BOLD));
+        assert!(!style.has_mode(ITALIC));
+        assert_eq!(
+            "styled".style(style).to_string(),
+            "\x1b[1;4;31;48;2;32;32;32mstyled\x1b[0m"
+        );
+        assert_eq!(style.apply("styled"), "styled".style(style));
+
+        let layered = "layered"
+            .bold()
+            .foreground(GREEN)
+            .style(Style::new().bold().italic());
+        assert_eq!(layered.to_string(), "\x1b[1;3;32mlayered\x1b[0m");
+
+        // Codes such as 53 (overline) do not fit in the mode bits.
+        assert_eq!(Style::new().mode(53), Style::new());
+        assert_eq!(Style::new().disable(53), Style::new());
+        assert!(!Style::new().mode(53).has_mode(53));
+    }
+
+    #[test]
+    fn test_indexed_colors() {
+        // The base colors are stored by their SGR codes, so numeric input is
+        // kept as a palette index where it would collide with one.
+        assert_eq!(ForegroundColor::indexed(1), ForegroundColor::Simple(RED));
+        assert_eq!(ForegroundColor::indexed(31), ForegroundColor::Indexed(31));
+        assert_eq!(ForegroundColor::indexed(208), ForegroundColor::Simple(208));
+        assert_eq!(BackgroundColor::indexed(37), BackgroundColor::Indexed(37));
+        assert_eq!(
+            ForegroundColor::Indexed(31).to_rgb(),
+            Some(Rgb::new(0, 135, 175))
+        );
+
+        for index in BLACK..=WHITE {
+            assert_eq!(
+                "text"
+                    .foreground(ForegroundColor::indexed(index))
+                    .to_string(),
+                format!("\x1b[38;5;{index}mtext\x1b[0m")
+            );
+            assert_eq!(
+                "text"
+                    .background(BackgroundColor::indexed(index))
+                    .to_string(),
+                format!("\x1b[48;5;{index}mtext\x1b[0m")
+            );
+
+            let style: Style = index.to_string().parse().unwrap();
+            assert_eq!(style.foreground, ForegroundColor::Indexed(index));
+            assert_eq!(style.to_string(), index.to_string());
+        }
+
+        assert_eq!(
+            StyledText::from_markup("<31>a</> <bg:33>b</> <3>c</>")
+                .unwrap()
+                .to_string(),
+            "\x1b[38;5;31ma\x1b[0m \x1b[48;5;33mb\x1b[0m \x1b[33mc\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_hash_colors() {
+        // These must never change, as users rely on them being stable.
+        assert_eq!(Rgb::from_hash("web-1", &[]), Rgb::new(234, 141, 175));
+        assert_eq!(Rgb::from_hash("web-2", &[]), Rgb::new(240, 144, 126));
+        assert_eq!(Rgb::from_hash(&42usize, &[]), Rgb::from_hash(&42u64, &[]));
+
+        assert_eq!(
+            Rgb::from_hash("db", &[]),
+            Rgb::from_hash(&String::from("db"), &[])
+        );
+        assert_ne!(Rgb::from_hash("web-1", &[]), Rgb::from_hash("web-2", &[]));
+
+        let palette = [
+            Rgb::new(255, 0, 0),
+            Rgb::new(0, 255, 0),
+            Rgb::new(0, 0, 255),
+        ];
+        assert!(palette.contains(&Rgb::from_hash("thread-3", &palette)));
+
+        let style = Style::for_key("web-1");
+        assert_eq!(
+            style.foreground_color(),
+            ForegroundColor::Rgb(Rgb::from_hash("web-1", &[]))
+        );
+    }
+
+    #[test]
+    fn test_harmonies() {
+        let seed = Rgb::new(52, 101, 164);
+        let hue = |color: Rgb| Oklch::from(color).hue;
+        let difference = |a: Rgb, b: Rgb| (hue(b) - hue(a)).rem_euclid(360.0);
+
+        let [first, second] = seed.complementary();
+        assert_eq!(first, seed);
+        assert!((difference(seed, second) - 180.0).abs() < 2.0);
+
+        let [left, middle, right] = seed.analogous();
+        assert_eq!(middle, seed);
+        assert!((difference(left, seed) - 30.0).abs() < 2.0);
+        assert!((difference(seed, right) - 30.0).abs() < 2.0);
+
+        for (color, expected) in seed.triadic().iter().zip([0.0, 120.0, 240.0]) {
+            assert!((difference(seed, *color) - expected).abs() < 2.0);
+        }
+        for (color, expected) in seed.tetradic().iter().zip([0.0, 90.0, 180.0, 270.0]) {
+            assert!((difference(seed, *color) - expected).abs() < 2.0);
+        }
+        for (color, expected) in seed.split_complementary().iter().zip([0.0, 150.0, 210.0]) {
+            assert!((difference(seed, *color) - expected).abs() < 2.0);
+        }
+
+        let tints = seed.tints(4);
+        assert_eq!(tints.len(), 4);
+        assert_eq!(tints[0], seed);
+        assert!(tints
+            .windows(2)
+            .all(|pair| pair[0].relative_luminance() < pair[1].relative_luminance()));
+
+        let shades = seed.shades(4);
+        assert!(shades
+            .windows(2)
+            .all(|pair| pair[0].relative_luminance() > pair[1].relative_luminance()));
+
+        let scale = seed.scale();
+        assert_eq!(scale.iter().count(), 10);
+        assert_eq!(scale.get(50), Some(scale.colors()[0]));
+        assert_eq!(scale.get(900), Some(scale.colors()[9]));
+        assert_eq!(scale.get(550), None);
+        assert!(scale
+            .colors()
+            .windows(2)
+            .all(|pair| pair[0].relative_luminance() > pair[1].relative_luminance()));
+    }
+
+    #[test]
+    fn test_color_vision_deficiency() {
+        let red = Rgb::new(200, 40, 40);
+        let green = Rgb::new(40, 160, 40);
+        let white = Rgb::new(255, 255, 255);
+
+        for deficiency in [
+            Deficiency::Protanopia,
+            Deficiency::Deuteranopia,
+            Deficiency::Tritanopia,
+            Deficiency::Achromatopsia,
+        ] {
+            assert_eq!(white.simulate(deficiency), white);
+        }
+
+        let gray = red.simulate(Deficiency::Achromatopsia);
+        assert_eq!(gray.red, gray.green);
+        assert_eq!(gray.green, gray.blue);
+
+        // Red and green become much harder to tell apart, and daltonizing
+        // them restores some of the difference.
+        for deficiency in [Deficiency::Protanopia, Deficiency::Deuteranopia] {
+            let before = red.distance(&green, Metric::Ciede2000);
+            let simulated = red
+                .simulate(deficiency)
+                .distance(&green.simulate(deficiency), Metric::Ciede2000);
+            let corrected = red.daltonize(deficiency).simulate(deficiency).distance(
+                &green.daltonize(deficiency).simulate(deficiency),
+                Metric::Ciede2000,
+            );
+
+            assert!(simulated < before / 2.0);
+            assert!(corrected > simulated);
+        }
+
+        let styled = "status".foreground(RED).background(Rgb::new(0, 0, 0));
+        let simulated = styled.clone().simulate(Deficiency::Achromatopsia);
+        let expected = Rgb::from(Simple(RED)).simulate(Deficiency::Achromatopsia);
+        assert_eq!(simulated.foreground, ForegroundColor::Rgb(expected));
+        assert_eq!(
+            simulated.background,
+            BackgroundColor::Rgb(Rgb::new(0, 0, 0))
+        );
+
+        let theme = Theme::DEFAULT.simulate(Deficiency::Achromatopsia);
+        assert_eq!(
+            theme.error.foreground_color(),
+            ForegroundColor::Rgb(expected)
+        );
+        assert!(theme.error.has_mode(BOLD));
+        assert_eq!(
+            Theme::MONOCHROME.simulate(Deficiency::Protanopia),
+            Theme::MONOCHROME
+        );
+
+        let text = "ab"
+            .gradient(&[red, green])
+            .simulate(Deficiency::Deuteranopia);
+        assert_eq!(
+            text.spans()[0].foreground,
+            ForegroundColor::Rgb(red.simulate(Deficiency::Deuteranopia))
+        );
+    }
+
+    #[test]
+    fn test_markup() {
+        let text = StyledText::from_markup("<red>error</red>: <b>{path}</b>").unwrap();
+        assert_eq!(text.plain_text(), "error: {path}");
+        assert_eq!(
+            text.to_string(),
+            "\x1b[31merror\x1b[0m: \x1b[1m{path}\x1b[0m"
+        );
+
+        let nested = StyledText::from_markup("<b bg:#202020>a<i 208>b</>c</b>").unwrap();
+        assert_eq!(
+            nested.to_string(),
+            "\x1b[1;48;2;32;32;32ma\x1b[0m\x1b[1;3;38;5;208;48;2;32;32;32mb\x1b[0m\x1b[1;48;2;32;32;32mc\x1b[0m"
+        );
+
+        let escaped = StyledText::from_markup("a \\< b \\\\ <bright-blue>c</>").unwrap();
+        assert_eq!(escaped.plain_text(), "a < b \\ c");
+        assert_eq!(escaped.spans()[1].foreground, ForegroundColor::Simple(12));
+        assert_eq!(
+            StyledText::from_markup(&markup::escape("<b>\\"))
+                .unwrap()
+                .plain_text(),
+            "<b>\\"
+        );
+
+        assert_eq!(
+            StyledText::from_markup("<red>a</b>"),
+            Err(MarkupError::MismatchedClose {
+                expected: "red".into(),
+                found: "b".into(),
+                position: 6
+            })
+        );
+        assert_eq!(
+            StyledText::from_markup("<#gg0000>a</>"),
+            Err(MarkupError::UnknownTag {
+                tag: "#gg0000".into(),
+                position: 0
+            })
+        );
+        assert_eq!(
+            StyledText::from_markup("<b>a"),
+            Err(MarkupError::UnclosedTag {
+                tag: "b".into(),
+                position: 0
+            })
+        );
+        assert_eq!(
+            StyledText::from_markup("a</>"),
+            Err(MarkupError::UnexpectedClose {
+                tag: "".into(),
+                position: 1
+            })
+        );
+        assert_eq!(
+            StyledText::from_markup("a <b"),
+            Err(MarkupError::UnterminatedTag { position: 2 })
+        );
+    }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn test_macros() {
+        use std::fmt::Write;
+
+        const ORANGE: Rgb = rgb!("#ff8800");
+        assert_eq!(ORANGE, Rgb::new(255, 136, 0));
+
+        const WARNING: Style = style!("b u yellow bg:#202020");
+        let warning = WARNING;
+        assert_eq!(
+            warning,
+            Style::new()
+                .bold()
+                .underline()
+                .foreground(YELLOW)
+                .background(Rgb::new(32, 32, 32))
+        );
+        assert_eq!(
+            style!("fg:208 bg:blue"),
+            Style::new().foreground(208).background(BLUE)
+        );
+        assert_eq!(
+            style!("BOLD Bright_Red bg:31"),
+            Style::new()
+                .bold()
+                .foreground(9)
+                .background(BackgroundColor::Indexed(31))
+        );
+        assert_eq!(style!("3"), Style::new().foreground(YELLOW));
+        assert_eq!(
+            style!("#f80"),
+            Style::new().foreground(Rgb::new(255, 136, 0))
+        );
+
+        let path = "src/lib.rs";
+        assert_eq!(
+            cformat!("{red}Error{/}: {bold}{}{/}", path),
+            format!("{}: {}", "Error".red(), path.bold())
+        );
+        assert_eq!(
+            cformat!("{bold}a {italic #ff0000}{path}{/} c{/} {{red}}"),
+            "\x1b[1ma \x1b[1;3;38;2;255;0;0msrc/lib.rs\x1b[0m\x1b[1m c\x1b[0m {red}"
+        );
+        assert_eq!(cformat!(r"{green}\n{/}"), "\x1b[32m\\n\x1b[0m");
+
+        let (b, red) = ("bold", "red");
+        assert_eq!(
+            cformat!("{b} {red:} {0} {fg:31}x{/}", 7),
+            "bold red 7 \x1b[38;5;31mx\x1b[0m"
+        );
+
+        let mut output = String::new();
+        cwrite!(output, "{bg:236}{}{/}", 1).unwrap();
+        assert_eq!(output, "\x1b[48;5;236m1\x1b[0m");
+    }
+
+    #[test]
+    fn test_const_constructors() {
+        const BRAND: Rgb = Rgb::from_hex_const("#FF8800");
+        const ACCENT: Rgb = Rgb::new(32, 32, 32);
+        const HEADER: Style = Style::new()
+            .bold()
+            .underline()
+            .with_foreground(ForegroundColor::Rgb(BRAND))
+            .with_background(BackgroundColor::Simple(BLACK));
+        const EMPHASIS: Style = HEADER.patch(
+            Style::new()
+                .italic()
+                .with_foreground(ForegroundColor::Rgb(ACCENT)),
+        );
+        const PASTEL: Oklch = Oklch::new(0.8, 0.1, 200.0);
+
+        assert_eq!(BRAND, Rgb::new(255, 136, 0));
+        assert_eq!(Rgb::from_hex_const("ff8800"), BRAND);
+        assert_eq!(
+            HEADER,
+            Style::new()
+                .bold()
+                .underline()
+                .foreground(BRAND)
+                .background(BLACK)
+        );
+        assert!(EMPHASIS.has_mode(ITALIC) && EMPHASIS.has_mode(BOLD));
+        assert_eq!(EMPHASIS.foreground_color(), ForegroundColor::Rgb(ACCENT));
+        assert_eq!(EMPHASIS.background_color(), BackgroundColor::Simple(BLACK));
+        assert_eq!(PASTEL.hue, 200.0);
+
+        assert!(std::panic::catch_unwind(|| Rgb::from_hex_const("#ff88")).is_err());
+    }
+
+    #[test]
+    fn test_theme() {
+        let default = Theme::default();
+        assert_eq!(default, Theme::DEFAULT);
+        assert_eq!(
+            default.error("failed").to_string(),
+            "\x1b[1;31mfailed\x1b[0m"
+        );
+        assert_eq!(default.muted("hint").to_string(), "\x1b[2mhint\x1b[0m");
+        assert_eq!(default.get("warning"), Some(default.warning));
+        assert_eq!(default.get("fatal"), None);
+        assert!(ROLES.iter().all(|role| default.get(role).is_some()));
+
+        let custom = Theme {
+            error: Style::new().foreground(Rgb::new(255, 85, 85)),
+            ..Theme::MONOCHROME
+        };
+        assert_eq!(
+            custom.error("failed").to_string(),
+            "\x1b[38;2;255;85;85mfailed\x1b[0m"
+        );
+        assert_eq!(custom.success("done").to_string(), "\x1b[mdone\x1b[0m");
+        assert_eq!(custom.get("error"), Some(custom.error));
+        assert_eq!(custom.get("success"), Some(Theme::MONOCHROME.success));
+    }
+
+    #[test]
+    fn test_adaptive_colors() {
+        let light = Rgb::new(0, 90, 160);
+        let dark = Rgb::new(120, 190, 255);
+        let link = Adaptive::new(light, dark);
+
+        assert_eq!(link.resolve(Appearance::Light), light);
+        assert_eq!(link.resolve(Appearance::Dark), dark);
+
+        assert_eq!(Appearance::from_colorfgbg("15;0"), Some(Appearance::Dark));
+        assert_eq!(Appearance::from_colorfgbg("0;15"), Some(Appearance::Light));
+        assert_eq!(
+            Appearance::from_colorfgbg("0;default;7"),
+            Some(Appearance::Light)
+        );
+        assert_eq!(
+            Appearance::from_colorfgbg("0;default"),
+            Some(Appearance::Dark)
+        );
+        assert_eq!(Appearance::from_colorfgbg("nonsense"), None);
+        assert_eq!(
+            Appearance::from_background(Rgb::new(250, 250, 245)),
+            Appearance::Light
+        );
+        assert_eq!(
+            Appearance::from_background(Rgb::new(30, 30, 46)),
+            Appearance::Dark
+        );
+
+        assert_eq!(ForegroundColor::from(link), ForegroundColor::Adaptive(link));
+        assert_eq!(BackgroundColor::from(link), BackgroundColor::Adaptive(link));
+        assert_eq!(
+            "docs".foreground(link).foreground,
+            ForegroundColor::Adaptive(link)
+        );
+    }
+
+    #[cfg(all(unix, feature = "query"))]
+    #[test]
+    fn test_query_colors() {
+        use std::{
+            io::{Read, Write},
+            net::Shutdown,
+            os::unix::net::UnixStream,
+            time::Duration,
+        };
+
+        // A socket pair stands in for the terminal, with the other end
+        // playing the terminal emulator.
+        let (mut tty, mut terminal) = UnixStream::pair().unwrap();
+        terminal
+            .write_all(b"\x1b]11;rgb:1e1e/1e1e/2e2e\x1b\\\x1b]4;1;rgb:cd/00/00\x07\x1b[?62;22c")
+            .unwrap();
+
+        let colors = query_colors(
+            &mut tty,
+            &[
+                ColorQuery::Background,
+                ColorQuery::Palette(1),
+                ColorQuery::Foreground,
+            ],
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!(
+            colors,
+            [Some(Rgb::new(30, 30, 46)), Some(Rgb::new(205, 0, 0)), None]
+        );
+
+        let mut sent = [0; 32];
+        let count = terminal.read(&mut sent).unwrap();
+        assert_eq!(
+            &sent[..count],
+            b"\x1b]11;?\x1b\\\x1b]4;1;?\x1b\\\x1b]10;?\x1b\\\x1b[c"
+        );
+
+        // Keys typed right after the reply are not consumed with it.
+        terminal
+            .write_all(b"\x1b]11;rgb:ff/ff/ff\x07\x1b[?62;22ctyped")
+            .unwrap();
+        assert_eq!(
+            query_background(&mut tty, Duration::from_secs(5)).unwrap(),
+            Some(Rgb::new(255, 255, 255))
+        );
+
+        let mut typed = [0; 5];
+        tty.read_exact(&mut typed).unwrap();
+        assert_eq!(&typed, b"typed");
+
+        assert_eq!(
+            color::parse_color_reply("rgb:f/8/0"),
+            Some(Rgb::new(255, 136, 0))
+        );
+        assert_eq!(
+            color::parse_color_reply("rgba:ffff/0000/8080/ffff"),
+            Some(Rgb::new(255, 0, 128))
+        );
+        assert_eq!(color::parse_color_reply("rgb:ffff/0000"), None);
+        assert_eq!(color::parse_color_reply("#ff0000"), None);
+
+        // A terminal which never replies times out, and the input which
+        // arrives afterwards is left for the application.
+        let result = query_background(&mut tty, Duration::from_millis(20));
+        assert!(matches!(result, Err(QueryError::Timeout)));
+
+        terminal.write_all(b"q").unwrap();
+        let mut key = [0; 1];
+        tty.read_exact(&mut key).unwrap();
+        assert_eq!(&key, b"q");
+
+        // A terminal which closes before replying to everything.
+        terminal.write_all(b"\x1b]11;rgb:ff/ff/ff\x07").unwrap();
+        terminal.shutdown(Shutdown::Write).unwrap();
+        assert_eq!(
+            query_background(&mut tty, Duration::from_secs(5)).unwrap(),
+            Some(Rgb::new(255, 255, 255))
+        );
+    }
+
+    #[test]
+    fn test_parse_hex_colors() {
+        assert_eq!(Rgb::from_hex("#ff8800"), Ok(Rgb::new(255, 136, 0)));
+        assert_eq!("FF8800".parse::<Rgb>(), Ok(Rgb::new(255, 136, 0)));
+        assert_eq!(
+            Rgb::from_hex("#ff88"),
+            Err(ParseColorError::InvalidLength(4))
+        );
+        assert_eq!(
+            "#ff88zz".parse::<Rgb>(),
+            Err(ParseColorError::InvalidDigit('z'))
+        );
+        assert!(std::panic::catch_unwind(|| Rgb::from("#ff88")).is_err());
+    }
+
+    #[cfg(feature = "schemes")]
+    #[test]
+    fn test_schemes() {
+        let colors = [
+            "181818", "282828", "383838", "585858", "b8b8b8", "d8d8d8", "e8e8e8", "f8f8f8",
+            "ab4642", "dc9656", "f7ca88", "a1b56c", "86c1b9", "7cafc2", "ba8baf", "a16946",
+        ];
+
+        let mut yaml = String::from(
+            "# Original format\nscheme: \"Default Dark\"\nauthor: 'Chris Kempson' # comment\n",
+        );
+        for (index, color) in colors.iter().enumerate() {
+            yaml.push_str(&format!("base{index:02X}: \"{color}\"\n"));
+        }
+
+        let scheme = Scheme::parse(&yaml).unwrap();
+        assert_eq!(scheme.name, "Default Dark");
+        assert_eq!(scheme.author, "Chris Kempson");
+        assert_eq!(scheme.base(0x08), Some(Rgb::new(171, 70, 66)));
+        assert!(!scheme.is_base24());
+        assert_eq!(scheme.appearance(), Appearance::Dark);
+        assert_eq!(scheme.terminal_palette().rgb(RED), Rgb::new(171, 70, 66));
+        assert_eq!(
+            scheme.terminal_palette().background,
+            Some(Rgb::new(24, 24, 24))
+        );
+
+        let theme = scheme.theme();
+        assert_eq!(
+            theme.error("failed").to_string(),
+            "\x1b[1;38;2;171;70;66mfailed\x1b[0m"
+        );
+
+        // The newer format nests the colors under `palette`.
+        let mut nested = String::from("system: \"base24\"\nname: \"Nested\"\npalette:\n");
+        let mut json = String::from("{\"name\": \"Nested\", \"variant\": \"dark\", \"palette\": {");
+        for index in 0..24 {
+            let color = colors[index % 16];
+            nested.push_str(&format!("  base{index:02x}: \"#{color}\"\n"));
+            json.push_str(&format!(
+                "{}\"base{index:02X}\": \"#{color}\"",
+                if index == 0 { "" } else { ", " }
+            ));
+        }
+        json.push_str("}, \"tags\": [1, true, null]}");
+
+        let from_yaml = Scheme::from_yaml(&nested).unwrap();
+        assert!(from_yaml.is_base24());
+        assert_eq!(Scheme::parse(&json), Ok(from_yaml));
+
+        let palette = scheme.palette().to_vec();
+        assert_eq!(
+            Scheme::new("Default Dark", "Chris Kempson", palette.clone()),
+            Some(scheme)
+        );
+        assert_eq!(Scheme::new("Short", "", palette[..8].to_vec()), None);
+
+        assert_eq!(
+            Scheme::from_yaml("scheme: x\nbase00: \"181818\""),
+            Err(SchemeError::MissingColor(String::from("base01")))
+        );
+        assert_eq!(
+            Scheme::from_yaml(&yaml.replace("ab4642", "ab46")),
+            Err(SchemeError::InvalidColor {
+                key: String::from("base08"),
+                error: ParseColorError::InvalidLength(4),
+            })
+        );
+        assert_eq!(
+            Scheme::from_yaml("scheme x"),
+            Err(SchemeError::Syntax { line: 1 })
+        );
+        assert_eq!(
+            Scheme::from_json("{\n\"name\": \"x\",\n}"),
+            Err(SchemeError::Syntax { line: 3 })
+        );
+
+        // Escapes followed by a multibyte character, or which are not valid
+        // JSON, are syntax errors rather than panics.
+        for name in ["\\é", "\\q", "\\u00é", "\\u+fff", "\\"] {
+            assert_eq!(
+                Scheme::from_json(&format!("{{\"name\":\"{name}\"}}")),
+                Err(SchemeError::Syntax { line: 1 })
+            );
+        }
+    }
+
+    #[test]
+    fn test_terminal_palette() {
+        let palette = TerminalPalette::default();
+        assert_eq!(palette.rgb(RED), Rgb::new(205, 0, 0));
+        assert_eq!(palette.rgb(9), Rgb::from(Simple(9)));
+        assert_eq!(palette.rgb(196), Rgb::new(255, 0, 0));
+        assert_eq!(palette.closest(Rgb::new(250, 10, 10), Metric::Ciede2000), 9);
+    }
+
+    #[cfg(feature = "schemes")]
+    #[test]
+    fn test_terminal_palette_formats() {
+        let palettes = [
+            TerminalPalette::from_alacritty(include_str!("../tests/fixtures/alacritty.toml")),
+            TerminalPalette::from_windows_terminal(include_str!(
+                "../tests/fixtures/windows-terminal.json"
+            )),
+            TerminalPalette::from_xresources(include_str!("../tests/fixtures/Xresources")),
+            TerminalPalette::from_kitty(include_str!("../tests/fixtures/kitty.conf")),
+        ];
+
+        for palette in palettes {
+            let palette = palette.unwrap();
+
+            assert_eq!(palette.background, Some(Rgb::new(29, 31, 33)));
+            assert_eq!(palette.foreground, Some(Rgb::new(197, 200, 198)));
+            assert_eq!(palette.rgb(RED), Rgb::new(204, 102, 102));
+            assert_eq!(palette.rgb(4), Rgb::new(129, 162, 190));
+            assert_eq!(palette.rgb(13), Rgb::new(195, 151, 216));
+            assert_eq!(palette.rgb(15), Rgb::new(234, 234, 234));
+        }
+
+        assert_eq!(
+            TerminalPalette::from_kitty("color1 #ff"),
+            Err(SchemeError::InvalidColor {
+                key: String::from("color1"),
+                error: ParseColorError::InvalidLength(2),
+            })
+        );
+
+        let partial = TerminalPalette::from_kitty("color1 #ff0000").unwrap();
+        assert_eq!(partial.rgb(1), Rgb::new(255, 0, 0));
+        assert_eq!(partial.rgb(2), TerminalPalette::XTERM.rgb(2));
+        assert_eq!(partial.background, None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_json::{from_str, json, to_value};
+
+        let orange = Rgb::new(255, 136, 0);
+        assert_eq!(to_value(orange).unwrap(), json!("#ff8800"));
+        assert_eq!(from_str::<Rgb>("\"#FF8800\"").unwrap(), orange);
+        assert!(from_str::<Rgb>("\"#ff88\"").is_err());
+
+        assert_eq!(
+            to_value(ForegroundColor::Simple(RED)).unwrap(),
+            json!("red")
+        );
+        assert_eq!(to_value(ForegroundColor::Simple(208)).unwrap(), json!(208));
+        assert_eq!(
+            to_value(BackgroundColor::Rgb(orange)).unwrap(),
+            json!("#ff8800")
+        );
+        assert_eq!(
+            from_str::<BackgroundColor>("\"bright-blue\"").unwrap(),
+            BackgroundColor::Simple(12)
+        );
+        assert_eq!(
+            from_str::<ForegroundColor>("236").unwrap(),
+            ForegroundColor::Simple(236)
+        );
+        assert_eq!(
+            from_str::<ForegroundColor>("31").unwrap(),
+            ForegroundColor::Indexed(31)
+        );
+        assert_eq!(
+            from_str::<BackgroundColor>("1").unwrap(),
+            BackgroundColor::Simple(RED)
+        );
+        assert_eq!(to_value(ForegroundColor::Indexed(31)).unwrap(), json!(31));
+        assert_eq!(
+            from_str::<ForegroundColor>("null").unwrap(),
+            ForegroundColor::Empty
+        );
+        assert!(from_str::<ForegroundColor>("256").is_err());
+        assert!(from_str::<ForegroundColor>("\"purple\"").is_err());
+
+        let adaptive = ForegroundColor::Adaptive(Adaptive::new(Rgb::new(0, 0, 0), orange));
+        let value = to_value(adaptive).unwrap();
+        assert_eq!(value, json!({ "light": "#000000", "dark": "#ff8800" }));
+        assert_eq!(
+            from_str::<ForegroundColor>(&value.to_string()).unwrap(),
+            adaptive
+        );
+
+        let style = Style::new()
+            .bold()
+            .italic()
+            .foreground(RED)
+            .background(Rgb::new(32, 32, 32));
+        let value = to_value(style).unwrap();
+        assert_eq!(
+            value,
+            json!({ "foreground": "red", "background": "#202020", "modes": ["bold", "italic"] })
+        );
+        assert_eq!(
+            to_value(Style::new().disable(BOLD)).unwrap(),
+            json!({ "modes": ["no-bold"] })
+        );
+        assert_eq!(
+            from_str::<Style>("{ \"modes\": [\"no-bold\"] }").unwrap(),
+            Style::new().disable(BOLD)
+        );
+        assert_eq!(from_str::<Style>(&value.to_string()).unwrap(), style);
+        assert_eq!(
+            from_str::<Style>("\"bold italic red on #202020\"").unwrap(),
+            style
+        );
+        assert_eq!(
+            from_str::<Style>("{ \"modes\": [\"u\"] }").unwrap(),
+            Style::new().underline()
+        );
+        assert_eq!(to_value(Style::new()).unwrap(), json!({ "modes": [] }));
+        assert!(from_str::<Style>("\"bold on\"").is_err());
+        assert!(from_str::<Style>("{ \"modes\": [\"loud\"] }").is_err());
+    }
+
+    #[test]
+    fn test_style_strings() {
+        let style: Style = "bold italic #ff0 on blue".parse().unwrap();
+        assert_eq!(
+            style,
+            Style::new()
+                .bold()
+                .italic()
+                .foreground(Rgb::new(255, 255, 0))
+                .background(BLUE)
+        );
+        assert_eq!(style.to_string(), "bold italic #ffff00 on blue");
+        assert_eq!(style.to_string().parse::<Style>().unwrap(), style);
+        assert_eq!("on blue #ff0 bold italic".parse::<Style>().unwrap(), style);
+        assert_eq!(
+            StyledText::from_markup("<b i #ff0 bg:blue>x</>").unwrap(),
+            StyledText::from(style.apply("x"))
+        );
+
+        // Like git, a second color is the background, and `normal` is no color.
+        assert_eq!(
+            "normal 236 ul".parse::<Style>().unwrap(),
+            Style::new().underline().background(236)
+        );
+        assert_eq!(Style::new().background(236).to_string(), "normal on 236");
+
+        let negated: Style = "no-bold nounderline bright-red".parse().unwrap();
+        assert!(negated.disables_mode(BOLD) && negated.disables_mode(UNDERLINE));
+        assert!(!negated.has_mode(BOLD));
+        assert_eq!(negated.to_string(), "no-bold no-underline bright-red");
+
+        let base = Style::new().bold().italic().foreground(RED);
+        let patched = base.patch(negated);
+        assert!(!patched.has_mode(BOLD) && patched.has_mode(ITALIC));
+        assert_eq!(patched.foreground_color(), ForegroundColor::Simple(9));
+        assert!(!patched.patch(Style::new().bold()).disables_mode(BOLD));
+
+        assert_eq!(
+            "text".bold().dim().style(negated).to_string(),
+            "\x1b[2;38;5;9mtext\x1b[0m"
+        );
+        assert_eq!(
+            "text".bold().dim().style("no-bold".parse().unwrap()),
+            "text".dim()
+        );
+
+        let adaptive =
+            Style::new().foreground(Adaptive::new(Rgb::new(0, 0, 0), Rgb::new(255, 255, 255)));
+        assert_eq!(adaptive.to_string(), "#000000/#ffffff");
+        assert_eq!(adaptive.to_string().parse::<Style>().unwrap(), adaptive);
+        assert_eq!(Style::new().to_string(), "");
+        assert_eq!("".parse::<Style>().unwrap(), Style::new());
+
+        assert_eq!(
+            "bold purple".parse::<Style>(),
+            Err(ParseStyleError::UnknownWord(String::from("purple")))
+        );
+        assert_eq!(
+            "red on".parse::<Style>(),
+            Err(ParseStyleError::MissingBackground)
+        );
+        assert_eq!(
+            "red blue green".parse::<Style>(),
+            Err(ParseStyleError::TooManyColors(String::from("green")))
+        );
+        assert_eq!(
+            "on blue red green".parse::<Style>(),
+            Err(ParseStyleError::TooManyColors(String::from("green")))
+        );
+        assert_eq!(
+            "red on blue on green".parse::<Style>(),
+            Err(ParseStyleError::TooManyColors(String::from("green")))
+        );
+    }
+
+    #[test]
+    fn test_ls_colors() {
+        assert_eq!(
+            Style::from_sgr("01;38;5;208;48;2;0;0;64"),
+            Some(
+                Style::new()
+                    .bold()
+                    .foreground(208)
+                    .background(Rgb::new(0, 0, 64))
+            )
+        );
+        assert_eq!(
+            Style::from_sgr("4;91;40"),
+            Some(Style::new().underline().foreground(9).background(BLACK))
+        );
+        assert_eq!(Style::from_sgr(""), Some(Style::new()));
+        assert_eq!(Style::from_sgr("38;5"), None);
+        assert_eq!(
+            Style::from_sgr("38;5;31;48;5;37"),
+            Some(
+                Style::new()
+                    .foreground(ForegroundColor::Indexed(31))
+                    .background(BackgroundColor::Indexed(37))
+            )
+        );
+        assert_eq!(Style::from_sgr("38;5;31").unwrap().to_sgr(), "38;5;31");
+        assert_eq!(Style::from_sgr("bold"), None);
+
+        let colors =
+            LsColors::parse("rs=0:di=01;34:ln=target:ex=01;32:*.tar=01;31:*README=33").unwrap();
+        assert_eq!(
+            colors.get(Indicator::Directory),
+            Some(Style::new().bold().foreground(BLUE))
+        );
+        assert_eq!(colors.get(Indicator::Link), None);
+        assert_eq!(
+            colors.style_for_name("backup.tar"),
+            Some(Style::new().bold().foreground(RED))
+        );
+        assert_eq!(
+            colors.style_for_name("BACKUP.TAR"),
+            Some(Style::new().bold().foreground(RED))
+        );
+        assert_eq!(
+            colors.style_for_name("README"),
+            Some(Style::new().foreground(YELLOW))
+        );
+        assert_eq!(colors.style_for_name("main.rs"), None);
+        assert_eq!(
+            LsColors::parse("di=01;34:*.tar"),
+            Err(LsColorsError {
+                line: 1,
+                entry: String::from("*.tar")
+            })
+        );
+
+        let database = "# Configuration file for dircolors\n\
+                        TERM xterm*\n\
+                        NORMAL 00\n\
+                        DIR 01;34 # directories\n\
+                        EXEC 01;32\n\
+                        .tar 01;31\n\
+                        *.gz 01;31\n";
+        let dircolors = LsColors::from_dircolors(database).unwrap();
+        assert_eq!(
+            dircolors.get(Indicator::Directory),
+            colors.get(Indicator::Directory)
+        );
+        assert_eq!(
+            dircolors.style_for_name("a.gz"),
+            Some(Style::new().bold().foreground(RED))
+        );
+        assert_eq!(dircolors.style_for_name("main.rs"), Some(Style::new()));
+        assert_eq!(
+            LsColors::from_dircolors("DIR 01;34\nEXEC\n")
+                .unwrap_err()
+                .line,
+            2
+        );
+
+        // Removes the directory when the test ends, even if it fails.
+        struct TempDir(std::path::PathBuf);
+
+        impl Drop for TempDir {
+            fn drop(&mut self) {
+                let _ = std::fs::remove_dir_all(&self.0);
+            }
+        }
+
+        let temp = TempDir(std::env::temp_dir().join(format!("iridescent-{}", std::process::id())));
+        let directory = &temp.0;
+        std::fs::create_dir_all(directory).unwrap();
+        let archive = directory.join("backup.tar");
+        std::fs::write(&archive, "").unwrap();
+
+        assert_eq!(
+            colors.style_for_path(directory),
+            colors.get(Indicator::Directory)
+        );
+        assert_eq!(
+            colors.style_for_path(&archive),
+            colors.style_for_name("backup.tar")
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{symlink, PermissionsExt};
+
+            let script = directory.join("run.tar");
+            std::fs::write(&script, "").unwrap();
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+            assert_eq!(
+                colors.style_for_path(&script),
+                colors.get(Indicator::Executable)
+            );
+
+            let link = directory.join("link");
+            symlink(&archive, &link).unwrap();
+            assert_eq!(
+                colors.style_for_path(&link),
+                colors.style_for_name("backup.tar")
+            );
+
+            let orphan = directory.join("orphan");
+            symlink(directory.join("missing"), &orphan).unwrap();
+            assert_eq!(dircolors.style_for_path(&orphan), Some(Style::new()));
+        }
+    }
+
+    #[test]
+    fn test_tool_colors() {
+        let style = Style::new()
+            .bold()
+            .disable(ITALIC)
+            .foreground(9)
+            .background(Rgb::new(32, 32, 32));
+        assert_eq!(style.to_sgr(), "1;23;91;48;2;32;32;32");
+        assert_eq!(Style::from_sgr(&style.to_sgr()), Some(style));
+        assert_eq!(
+            Style::new().foreground(208).background(BLUE).to_sgr(),
+            "38;5;208;44"
+        );
+
+        let grep = GrepColors::parse("mt=01;32:fn=34:ne").unwrap();
+        assert_eq!(grep.selected_match, Style::new().bold().foreground(GREEN));
+        assert_eq!(grep.context_match, grep.selected_match);
+        assert_eq!(grep.file_name, Style::new().foreground(BLUE));
+        assert_eq!(grep.separator, GrepColors::DEFAULT.separator);
+        assert!(grep.no_erase && !grep.reverse);
+        assert_eq!(
+            grep.to_string(),
+            "ms=1;32:mc=1;32:sl=:cx=:fn=34:ln=32:bn=32:se=36:ne"
+        );
+        assert_eq!(GrepColors::parse(&grep.to_string()), Ok(grep));
+
+        let gcc = GccColors::parse("error=01;31:warning=01;35:note=01;36:quote=01;4").unwrap();
+        assert_eq!(gcc.quote, Style::new().bold().underline());
+        assert_eq!(gcc.locus, GccColors::DEFAULT.locus);
+        assert_eq!(
+            gcc.get("fixit-insert"),
+            Some(Style::new().foreground(GREEN))
+        );
+        assert_eq!(GccColors::parse(&gcc.to_string()), Ok(gcc));
+        assert_eq!(GccColors::parse(""), Ok(GccColors::PLAIN));
+        assert_eq!(
+            GccColors::parse("error=bold"),
+            Err(ColorsError {
+                line: 1,
+                entry: String::from("error=bold")
+            })
+        );
+
+        let git = Style::from_git("reset ul brightred nobold #202020").unwrap();
+        assert_eq!(
+            git,
+            Style::new()
+                .underline()
+                .disable(BOLD)
+                .foreground(9)
+                .background(Rgb::new(32, 32, 32))
+        );
+        assert_eq!(git.to_git(), "ul nobold brightred #202020");
+        assert_eq!(
+            Style::new().invert().background(236).to_git(),
+            "reverse normal 236"
+        );
+
+        // Git reads numbers as palette indexes, even those which are also SGR
+        // codes of the base colors.
+        let indexed = Style::from_git("31 1").unwrap();
+        assert_eq!(indexed.foreground, ForegroundColor::Indexed(31));
+        assert_eq!(indexed.background, BackgroundColor::Simple(RED));
+        assert_eq!(indexed.to_git(), "31 red");
+        assert_eq!(Style::from_git(&indexed.to_git()).unwrap(), indexed);
+
+        let config = "[core]\n\
+                      \teditor = vim\n\
+                      [color]\n\
+                      \tui = auto\n\
+                      [color \"diff\"]\n\
+                      \tmeta = bold yellow ; headers\n\
+                      \tFrag = \"magenta #202020\"\n\
+                      [color.status]\n\
+                      \tadded = green\n";
+        let mut colors = GitColors::parse(config).unwrap();
+        assert_eq!(
+            colors.get("color.diff.meta"),
+            Some(Style::new().bold().foreground(YELLOW))
+        );
+        assert_eq!(
+            colors.get("diff.frag"),
+            Some(
+                Style::new()
+                    .foreground(MAGENTA)
+                    .background(Rgb::new(32, 32, 32))
+            )
+        );
+        assert_eq!(
+            colors.get("status.added"),
+            Some(Style::new().foreground(GREEN))
+        );
+        assert_eq!(colors.get("ui"), None);
+
+        colors.set("color.diff.meta", Style::new().foreground(CYAN));
+        assert_eq!(
+            colors.to_string(),
+            "[color \"diff\"]\n\tmeta = cyan\n\tfrag = \"magenta #202020\"\n\
+             [color \"status\"]\n\tadded = green\n"
+        );
+        assert_eq!(GitColors::parse(&colors.to_string()), Ok(colors));
+        assert_eq!(
+            GitColors::parse("[color \"diff\"]\nmeta = purple\n")
+                .unwrap_err()
+                .line,
+            2
+        );
+    }
+
+    #[test]
+    fn test_html() {
+        assert_eq!(
+            "<b> & \"c\"".bold().foreground(RED).to_html(),
+            "<span style=\"font-weight: bold; color: #cd0000\">&lt;b&gt; &amp; &quot;c&quot;</span>"
+        );
+        assert_eq!(StyledString::new("plain").to_html(), "plain");
+
+        let text: StyledText = [
+            "error".bold().underline().strike().foreground(9),
+            StyledString::new(": "),
+            "orange".foreground(208).background(Rgb::new(32, 32, 32)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            text.to_html(),
+            "<span style=\"font-weight: bold; text-decoration: underline line-through; \
+             color: #ff0000\">error</span>: \
+             <span style=\"color: #ff8700; background-color: #202020\">orange</span>"
+        );
+
+        let mut palette = TerminalPalette::XTERM;
+        palette.colors[1] = Rgb::new(204, 102, 102);
+        palette.background = Some(Rgb::new(29, 31, 33));
+
+        let html = Html::new()
+            .palette(palette)
+            .styling(HtmlStyling::Classes)
+            .class_prefix("term-");
+        assert_eq!(
+            html.render_span(&"x".italic().foreground(RED).background(3)),
+            "<span class=\"term-italic term-fg-1 term-bg-3\">x</span>"
+        );
+        assert_eq!(
+            html.render(&text),
+            "<span class=\"term-bold term-underline term-strike term-fg-9\">error</span>: \
+             <span style=\"color: #ff8700; background-color: #202020\">orange</span>"
+        );
+        assert_eq!(
+            html.render_span(&"inverted".invert().foreground(RED)),
+            "<span class=\"term-bg-1\" style=\"color: #1d1f21\">inverted</span>"
+        );
+
+        let stylesheet = html.stylesheet();
+        assert!(stylesheet.starts_with(".term-terminal { background-color: #1d1f21; }\n"));
+        assert!(stylesheet.contains(".term-fg-1 { color: #cc6666; }\n"));
+        assert!(stylesheet.contains(".term-bg-15 { background-color: #ffffff; }\n"));
+
+        let html = Html::new()
+            .styling(HtmlStyling::Classes)
+            .class_prefix("\"><x {y}");
+        assert_eq!(
+            html.render_span(&"z".bold()),
+            "<span class=\"xybold\">z</span>"
+        );
+        assert!(html.stylesheet().contains(".xybold { font-weight: bold; }"));
+    }
+
+    #[test]
+    fn test_ansi() {
+        let output = "\x1b[0m\x1b[1m\x1b[38;5;9merror[E0308]\x1b[0m\x1b[0m\x1b[1m: mismatched types\x1b[0m\r\n\
+                      \x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07 \x1b[?25l\x1b[2K<tag>\x1b(B\x07";
+        let text = StyledText::from_ansi(output);
+        assert_eq!(
+            text.spans(),
+            [
+                "error[E0308]".bold().foreground(9),
+                ": mismatched types".bold(),
+                StyledString::new("\nlink <tag>"),
+            ]
+        );
+        assert_eq!(
+            StyledText::from_ansi_reader(output.as_bytes()).unwrap(),
+            text
+        );
+
+        let text =
+            StyledText::from_ansi("\x1b[1;3;31ma\x1b[22mb\x1b[39;44mc\x1b[99;1md\x1b[38;5me");
+        assert_eq!(
+            text.spans(),
+            [
+                "a".bold().italic().foreground(RED),
+                "b".italic().foreground(RED),
+                "c".italic().background(BLUE),
+                "de".bold().italic().background(BLUE),
+            ]
+        );
+
+        assert_eq!(
+            Html::new().render_ansi("\x1b[32m<script>\x1b[0m & done"),
+            "<span style=\"color: #00cd00\">&lt;script&gt;</span> &amp; done"
+        );
+        assert_eq!(
+            Html::new()
+                .styling(HtmlStyling::Classes)
+                .render_ansi_reader(&b"\x1b[4;91mwarning\xff"[..])
+                .unwrap(),
+            "<span class=\"ansi-underline ansi-fg-9\">warning\u{fffd}</span>"
+        );
+
+        // 256 color indexes which are also SGR codes of the base colors.
+        assert_eq!(
+            StyledText::from_ansi("\x1b[38;5;33;48;5;31mx").spans(),
+            [StyledString::new("x")
+                .foreground(ForegroundColor::Indexed(33))
+                .background(BackgroundColor::Indexed(31))]
+        );
+        assert_eq!(
+            Html::new().render_ansi("\x1b[38;5;33mx\x1b[33my"),
+            "<span style=\"color: #0087ff\">x</span><span style=\"color: #cdcd00\">y</span>"
+        );
+
+        // Cargo redraws its progress bar after a carriage return, and
+        // replaces it with the next message.
+        let cargo = "\x1b[1m\x1b[32m   Compiling\x1b[0m foo v0.1.0\n\
+                     \x1b[1m\x1b[36m    Building\x1b[0m [=>  ] 1/2: bar\r\x1b[K\
+                     \x1b[1m\x1b[32m   Compiling\x1b[0m bar v0.1.0\r\n";
+        let text = StyledText::from_ansi(cargo);
+        assert_eq!(
+            text.plain_text(),
+            "   Compiling foo v0.1.0\n   Compiling bar v0.1.0\n"
+        );
+        assert_eq!(text.spans()[2], "   Compiling".bold().foreground(GREEN));
+        assert_eq!(StyledText::from_ansi("50%\r100%\r").plain_text(), "100%");
+
+        // Parameters which cannot be parsed are skipped, and colors can use
+        // sub-parameters.
+        assert_eq!(
+            StyledText::from_ansi("\x1b[1;300;58;5;9mx\x1b[38:2::255:136:0;48:5:236;4:3my").spans(),
+            [
+                "x".bold(),
+                "y".bold()
+                    .underline()
+                    .foreground(Rgb::new(255, 136, 0))
+                    .background(236),
+            ]
+        );
+    }
 }
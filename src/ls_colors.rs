@@ -0,0 +1,346 @@
+use std::{
+    env,
+    error::Error,
+    fmt::Display,
+    fs::{self, Metadata},
+    path::Path,
+};
+
+use crate::style::Style;
+
+/// Represents the kinds of entries which `LS_COLORS` assigns styles to, along
+/// with their two letter codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Indicator {
+    /// `no`: the default for anything without a more specific style.
+    Normal,
+    /// `fi`: a regular file.
+    File,
+    /// `di`: a directory.
+    Directory,
+    /// `ln`: a symbolic link.
+    Link,
+    /// `or`: a symbolic link whose target does not exist.
+    Orphan,
+    /// `mi`: a missing file, which an orphaned link points to.
+    Missing,
+    /// `pi`: a named pipe.
+    Pipe,
+    /// `so`: a socket.
+    Socket,
+    /// `do`: a door.
+    Door,
+    /// `bd`: a block device.
+    BlockDevice,
+    /// `cd`: a character device.
+    CharDevice,
+    /// `ex`: an executable file.
+    Executable,
+    /// `su`: a file which is setuid.
+    Setuid,
+    /// `sg`: a file which is setgid.
+    Setgid,
+    /// `ca`: a file with capabilities.
+    Capability,
+    /// `mh`: a file with multiple hard links.
+    MultiHardLink,
+    /// `tw`: a directory which is both sticky and writable by others.
+    StickyOtherWritable,
+    /// `ow`: a directory which is writable by others.
+    OtherWritable,
+    /// `st`: a directory which is sticky.
+    Sticky,
+}
+
+/// The two letter codes and `dircolors` keywords of each indicator.
+const INDICATORS: [(Indicator, &str, &[&str]); 19] = [
+    (Indicator::Normal, "no", &["NORMAL", "NORM"]),
+    (Indicator::File, "fi", &["FILE"]),
+    (Indicator::Directory, "di", &["DIR"]),
+    (Indicator::Link, "ln", &["LINK", "LNK", "SYMLINK"]),
+    (Indicator::Orphan, "or", &["ORPHAN"]),
+    (Indicator::Missing, "mi", &["MISSING"]),
+    (Indicator::Pipe, "pi", &["FIFO", "PIPE"]),
+    (Indicator::Socket, "so", &["SOCK"]),
+    (Indicator::Door, "do", &["DOOR"]),
+    (Indicator::BlockDevice, "bd", &["BLK", "BLOCK"]),
+    (Indicator::CharDevice, "cd", &["CHR", "CHAR"]),
+    (Indicator::Executable, "ex", &["EXEC"]),
+    (Indicator::Setuid, "su", &["SETUID"]),
+    (Indicator::Setgid, "sg", &["SETGID"]),
+    (Indicator::Capability, "ca", &["CAPABILITY"]),
+    (Indicator::MultiHardLink, "mh", &["MULTIHARDLINK"]),
+    (
+        Indicator::StickyOtherWritable,
+        "tw",
+        &["STICKY_OTHER_WRITABLE"],
+    ),
+    (Indicator::OtherWritable, "ow", &["OTHER_WRITABLE"]),
+    (Indicator::Sticky, "st", &["STICKY"]),
+];
+
+/// Represents an entry of `LS_COLORS` or a line of a `dircolors` database
+/// which could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LsColorsError {
+    /// The line of the entry, which is always 1 for `LS_COLORS`.
+    pub line: usize,
+    /// The entry or line, without comments.
+    pub entry: String,
+}
+
+impl Display for LsColorsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid entry `{}` on line {}", self.entry, self.line)
+    }
+}
+
+impl Error for LsColorsError {}
+
+/// The styles `ls` uses for each kind of file and extension, as configured by
+/// the `LS_COLORS` environment variable or a `dircolors` database.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LsColors {
+    indicators: Vec<(Indicator, Style)>,
+    // Patterns such as `*.tar`, which match the end of file names.
+    suffixes: Vec<(String, Style)>,
+    // Whether links use the style of their target, set by `ln=target`.
+    link_target: bool,
+}
+
+impl LsColors {
+    /// Reads the `LS_COLORS` environment variable, returning `None` if it is
+    /// not set or is invalid.
+    #[must_use]
+    pub fn from_env() -> Option<LsColors> {
+        LsColors::parse(&env::var("LS_COLORS").ok()?).ok()
+    }
+
+    /// Parses the value of `LS_COLORS`, such as `"di=01;34:*.tar=01;31"`.
+    pub fn parse(value: &str) -> Result<LsColors, LsColorsError> {
+        let mut colors = LsColors::default();
+
+        for entry in value.split(':').filter(|entry| !entry.is_empty()) {
+            let error = || LsColorsError {
+                line: 1,
+                entry: entry.to_string(),
+            };
+
+            let (key, sgr) = entry.split_once('=').ok_or_else(error)?;
+
+            if key.starts_with('*') {
+                colors.set_suffix(key, sgr).ok_or_else(error)?;
+            } else if let Some((indicator, _, _)) =
+                INDICATORS.iter().find(|(_, code, _)| *code == key)
+            {
+                colors.set(*indicator, sgr).ok_or_else(error)?;
+            }
+        }
+
+        Ok(colors)
+    }
+
+    /// Parses a `dircolors` database, such as the output of
+    /// `dircolors --print-database`. Settings for specific terminals are
+    /// applied regardless of the terminal.
+    pub fn from_dircolors(database: &str) -> Result<LsColors, LsColorsError> {
+        let mut colors = LsColors::default();
+
+        for (index, line) in database.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = || LsColorsError {
+                line: index + 1,
+                entry: line.to_string(),
+            };
+
+            let mut words = line.split_whitespace();
+            let (Some(key), Some(sgr)) = (words.next(), words.next()) else {
+                return Err(error());
+            };
+
+            if key.starts_with('*') {
+                colors.set_suffix(key, sgr).ok_or_else(error)?;
+            } else if let Some(extension) = key.strip_prefix('.') {
+                colors
+                    .set_suffix(&format!("*.{extension}"), sgr)
+                    .ok_or_else(error)?;
+            } else if let Some((indicator, _, _)) = INDICATORS
+                .iter()
+                .find(|(_, _, keywords)| keywords.contains(&key.to_ascii_uppercase().as_str()))
+            {
+                colors.set(*indicator, sgr).ok_or_else(error)?;
+            }
+        }
+
+        Ok(colors)
+    }
+
+    fn set(&mut self, indicator: Indicator, sgr: &str) -> Option<()> {
+        if indicator == Indicator::Link && sgr.eq_ignore_ascii_case("target") {
+            self.link_target = true;
+            return Some(());
+        }
+
+        let style = Style::from_sgr(sgr)?;
+        self.indicators
+            .retain(|(existing, _)| *existing != indicator);
+        self.indicators.push((indicator, style));
+
+        Some(())
+    }
+
+    fn set_suffix(&mut self, pattern: &str, sgr: &str) -> Option<()> {
+        let suffix = pattern.strip_prefix('*')?.to_string();
+        let style = Style::from_sgr(sgr)?;
+
+        self.suffixes.retain(|(existing, _)| *existing != suffix);
+        self.suffixes.push((suffix, style));
+
+        Some(())
+    }
+
+    /// Returns the style of an indicator, if it is set.
+    #[must_use]
+    pub fn get(&self, indicator: Indicator) -> Option<Style> {
+        self.indicators
+            .iter()
+            .find(|(existing, _)| *existing == indicator)
+            .map(|(_, style)| *style)
+    }
+
+    /// Returns the style of a regular file with the given name, matching
+    /// patterns such as `*.tar` case-sensitively first, and then
+    /// case-insensitively, like GNU `ls`.
+    #[must_use]
+    pub fn style_for_name(&self, name: &str) -> Option<Style> {
+        let lowercase = name.to_ascii_lowercase();
+
+        self.suffixes
+            .iter()
+            .find(|(suffix, _)| name.ends_with(suffix.as_str()))
+            .or_else(|| {
+                self.suffixes
+                    .iter()
+                    .find(|(suffix, _)| lowercase.ends_with(&suffix.to_ascii_lowercase()))
+            })
+            .map(|(_, style)| *style)
+            .or_else(|| self.fallback(Indicator::File))
+    }
+
+    /// Returns the style of the file at the given path, based on its type,
+    /// permissions and name, or `None` if `ls` would not style it. Links are
+    /// not followed, unless `LS_COLORS` sets `ln=target`.
+    #[must_use]
+    pub fn style_for_path(&self, path: &Path) -> Option<Style> {
+        let Ok(metadata) = fs::symlink_metadata(path) else {
+            return self.fallback(Indicator::Missing);
+        };
+
+        if metadata.file_type().is_symlink() {
+            return match fs::metadata(path) {
+                // Like GNU `ls`, extensions are matched against the name of
+                // the target rather than the link.
+                Ok(target) if self.link_target => {
+                    let target_path = fs::read_link(path).unwrap_or_default();
+                    self.style_for_metadata(&target_path, &target)
+                }
+                Ok(_) => self.fallback(Indicator::Link),
+                Err(_) => self
+                    .get(Indicator::Orphan)
+                    .or_else(|| self.fallback(Indicator::Link)),
+            };
+        }
+
+        self.style_for_metadata(path, &metadata)
+    }
+
+    /// Returns the style of a file from metadata that has already been read,
+    /// without following links. See `LsColors::style_for_path`.
+    #[must_use]
+    pub fn style_for_metadata(&self, path: &Path, metadata: &Metadata) -> Option<Style> {
+        let file_type = metadata.file_type();
+
+        if file_type.is_symlink() {
+            return self.fallback(Indicator::Link);
+        }
+
+        if let Some(indicator) = special_indicator(metadata) {
+            if let Some(style) = self.get(indicator) {
+                return Some(style);
+            }
+        }
+
+        if file_type.is_dir() {
+            return self.fallback(Indicator::Directory);
+        }
+
+        if !file_type.is_file() {
+            return self.fallback(Indicator::File);
+        }
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        self.style_for_name(&name)
+    }
+
+    /// Returns the style of an indicator, falling back to the style of
+    /// regular files and then the default style when it is not set.
+    fn fallback(&self, indicator: Indicator) -> Option<Style> {
+        self.get(indicator)
+            .or_else(|| self.get(Indicator::File))
+            .or_else(|| self.get(Indicator::Normal))
+    }
+}
+
+/// Returns the indicator for the type and permissions of a file, other than
+/// regular files and directories without special permissions.
+#[cfg(unix)]
+fn special_indicator(metadata: &Metadata) -> Option<Indicator> {
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+    let file_type = metadata.file_type();
+    let mode = metadata.mode();
+
+    let indicator = if file_type.is_fifo() {
+        Indicator::Pipe
+    } else if file_type.is_socket() {
+        Indicator::Socket
+    } else if file_type.is_block_device() {
+        Indicator::BlockDevice
+    } else if file_type.is_char_device() {
+        Indicator::CharDevice
+    } else if file_type.is_dir() {
+        match (mode & 0o1000 != 0, mode & 0o002 != 0) {
+            (true, true) => Indicator::StickyOtherWritable,
+            (false, true) => Indicator::OtherWritable,
+            (true, false) => Indicator::Sticky,
+            (false, false) => return None,
+        }
+    } else if mode & 0o4000 != 0 {
+        Indicator::Setuid
+    } else if mode & 0o2000 != 0 {
+        Indicator::Setgid
+    } else if mode & 0o111 != 0 {
+        Indicator::Executable
+    } else if metadata.nlink() > 1 {
+        Indicator::MultiHardLink
+    } else {
+        return None;
+    };
+
+    Some(indicator)
+}
+
+/// Returns the indicator for the type and permissions of a file. Only
+/// directories and regular files exist outside of Unix.
+#[cfg(not(unix))]
+fn special_indicator(_: &Metadata) -> Option<Indicator> {
+    None
+}
@@ -0,0 +1,180 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{
+    style::{from_grammar, to_background, Style},
+    styled_text::StyledText,
+};
+
+/// Represents an error found while parsing markup.
+///
+/// Every position is the byte offset of the offending tag in the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MarkupError {
+    /// A tag contained a word which is not a mode or a color.
+    UnknownTag { tag: String, position: usize },
+    /// A `<` was never followed by a closing `>`.
+    UnterminatedTag { position: usize },
+    /// A closing tag was found without a matching opening tag.
+    UnexpectedClose { tag: String, position: usize },
+    /// A closing tag did not match the most recently opened tag.
+    MismatchedClose {
+        expected: String,
+        found: String,
+        position: usize,
+    },
+    /// The input ended while a tag was still open.
+    UnclosedTag { tag: String, position: usize },
+}
+
+impl Display for MarkupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarkupError::UnknownTag { tag, position } => {
+                write!(f, "unknown tag `<{tag}>` at position {position}")
+            }
+            MarkupError::UnterminatedTag { position } => {
+                write!(f, "unterminated tag at position {position}")
+            }
+            MarkupError::UnexpectedClose { tag, position } => {
+                write!(
+                    f,
+                    "closing tag `</{tag}>` at position {position} was never opened"
+                )
+            }
+            MarkupError::MismatchedClose {
+                expected,
+                found,
+                position,
+            } => write!(
+                f,
+                "expected `</{expected}>` but found `</{found}>` at position {position}"
+            ),
+            MarkupError::UnclosedTag { tag, position } => {
+                write!(f, "tag `<{tag}>` at position {position} was never closed")
+            }
+        }
+    }
+}
+
+impl Error for MarkupError {}
+
+/// Parses the contents of an opening tag into a style. A tag holds one or more
+/// space separated words, each being a mode (`b`, `bold`), a foreground color
+/// (`red`, `#ff8800`, `208`) or a background color (`bg:blue`).
+fn parse_tag(tag: &str) -> Option<Style> {
+    let tag = iridescent_grammar::parse_tag(tag).ok()?;
+    let mut style = Style::new();
+
+    for mode in tag.modes {
+        style = style.mode(mode);
+    }
+    if let Some(color) = tag.foreground {
+        style = style.foreground(from_grammar(color));
+    }
+    if let Some(color) = tag.background {
+        style = style.background(to_background(from_grammar(color)));
+    }
+
+    Some(style)
+}
+
+/// Escapes text so that it can be safely inserted into markup, e.g. when it
+/// comes from user input.
+#[must_use]
+pub fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('<', "\\<")
+}
+
+impl StyledText {
+    /// Parses text containing style tags into styled text.
+    ///
+    /// Tags hold one or more space separated words, each being a mode (`b` or
+    /// `bold`, `i`, `u`, `s`, `dim`, `blink`, `invert`, `hidden`), a color name
+    /// (`red`, `bright-blue`), a hexadecimal color (`#ff8800`), an 8-bit color
+    /// index (`208`) or a background color prefixed with `bg:`. Tags can be
+    /// nested, and are closed with either their own name (`</red>`), the first
+    /// word of a tag with several words (`</b>` for `<b red>`), or `</>`.
+    /// A literal `<` or `\` is written as `\<` or `\\`.
+    pub fn from_markup(input: &str) -> Result<StyledText, MarkupError> {
+        // Each open tag remembers its name, where it started and the style of
+        // its contents, including the styles of the tags around it.
+        let mut stack: Vec<(&str, usize, Style)> = Vec::new();
+        let mut text = StyledText::new();
+        let mut buffer = String::new();
+
+        let current = |stack: &[(&str, usize, Style)]| {
+            stack.last().map_or_else(Style::new, |(_, _, style)| *style)
+        };
+
+        let mut chars = input.char_indices();
+        while let Some((position, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => buffer.push(escaped),
+                    None => buffer.push('\\'),
+                },
+                '<' => {
+                    let end = input[position..]
+                        .find('>')
+                        .map(|offset| position + offset)
+                        .ok_or(MarkupError::UnterminatedTag { position })?;
+                    let tag = input[position + 1..end].trim();
+
+                    if !buffer.is_empty() {
+                        text.push(current(&stack).apply(&buffer));
+                        buffer.clear();
+                    }
+
+                    if let Some(name) = tag.strip_prefix('/') {
+                        let name = name.trim();
+                        let (expected, _, _) =
+                            stack.pop().ok_or_else(|| MarkupError::UnexpectedClose {
+                                tag: name.to_string(),
+                                position,
+                            })?;
+
+                        // Tags with several words can be closed using the
+                        // whole tag or just its first word.
+                        let first_word = expected.split_whitespace().next();
+                        if !name.is_empty() && name != expected && Some(name) != first_word {
+                            return Err(MarkupError::MismatchedClose {
+                                expected: expected.to_string(),
+                                found: name.to_string(),
+                                position,
+                            });
+                        }
+                    } else {
+                        let style =
+                            parse_tag(tag).filter(|_| !tag.is_empty()).ok_or_else(|| {
+                                MarkupError::UnknownTag {
+                                    tag: tag.to_string(),
+                                    position,
+                                }
+                            })?;
+
+                        stack.push((tag, position, current(&stack).patch(style)));
+                    }
+
+                    // Skip past the rest of the tag.
+                    for _ in input[position + 1..=end].chars() {
+                        chars.next();
+                    }
+                }
+                c => buffer.push(c),
+            }
+        }
+
+        if let Some((tag, position, _)) = stack.pop() {
+            return Err(MarkupError::UnclosedTag {
+                tag: tag.to_string(),
+                position,
+            });
+        }
+
+        if !buffer.is_empty() {
+            text.push(current(&stack).apply(&buffer));
+        }
+
+        Ok(text)
+    }
+}
@@ -0,0 +1,267 @@
+#[cfg(feature = "schemes")]
+use crate::{
+    color::{parse_color_reply, ParseColorError},
+    scheme::{json_entries, SchemeError},
+};
+use crate::{
+    color::{Rgb, Simple, XTERM_BASE_COLORS},
+    constants::{BLACK, WHITE},
+    distance::Metric,
+};
+
+/// The names of the 8 base colors, in palette order.
+#[cfg(feature = "schemes")]
+const BASE_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// The 16 colors of a terminal's palette, along with its default foreground
+/// and background colors, if known. This is what `Simple` colors actually
+/// look like in that terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TerminalPalette {
+    /// The colors 0 to 15: the 8 base colors followed by their bright
+    /// variants.
+    pub colors: [Rgb; 16],
+    pub foreground: Option<Rgb>,
+    pub background: Option<Rgb>,
+}
+
+impl TerminalPalette {
+    /// The default xterm palette, which is also used to approximate `Simple`
+    /// colors elsewhere in this library.
+    pub const XTERM: TerminalPalette = TerminalPalette {
+        colors: XTERM_BASE_COLORS,
+        foreground: None,
+        background: None,
+    };
+
+    /// Returns the color shown for a `Simple` color. The base color constants
+    /// (`BLACK` through `WHITE`) and indexes 0 to 15 use the palette, while
+    /// the rest of the 256 colors are the same in every terminal.
+    #[must_use]
+    pub fn rgb(&self, color: u8) -> Rgb {
+        match color {
+            code @ BLACK..=WHITE => self.colors[usize::from(code - BLACK)],
+            index @ 0..=15 => self.colors[usize::from(index)],
+            index => Rgb::from(Simple(index)),
+        }
+    }
+
+    /// Returns the index of the palette color closest to the given color,
+    /// using the given metric. Useful for quantizing colors for terminals
+    /// that only support the 16 colors.
+    #[must_use]
+    pub fn closest(&self, color: Rgb, metric: Metric) -> u8 {
+        // The palette is never empty, and has fewer than 256 colors.
+        color
+            .closest(&self.colors, metric)
+            .and_then(|index| u8::try_from(index).ok())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for TerminalPalette {
+    fn default() -> Self {
+        Self::XTERM
+    }
+}
+
+#[cfg(feature = "schemes")]
+impl TerminalPalette {
+    /// Parses the colors of an Alacritty TOML configuration, from the
+    /// `[colors.primary]`, `[colors.normal]` and `[colors.bright]` tables.
+    /// Missing colors are taken from the xterm palette.
+    pub fn from_alacritty(source: &str) -> Result<TerminalPalette, SchemeError> {
+        let mut palette = TerminalPalette::XTERM;
+        let mut table = String::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line = line.split(" #").next().unwrap_or_default().trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[') {
+                table = name.trim_end_matches(']').trim().to_string();
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(SchemeError::Syntax { line: index + 1 })?;
+            let key = key.trim();
+
+            let offset = match table.as_str() {
+                "colors.primary" => {
+                    palette.set(key, value, |name| match name {
+                        "foreground" => Some(16),
+                        "background" => Some(17),
+                        _ => None,
+                    })?;
+                    continue;
+                }
+                "colors.normal" => 0,
+                "colors.bright" => 8,
+                _ => continue,
+            };
+
+            palette.set(key, value, |name| {
+                BASE_NAMES
+                    .iter()
+                    .position(|base| *base == name)
+                    .map(|position| position + offset)
+            })?;
+        }
+
+        Ok(palette)
+    }
+
+    /// Parses a Windows Terminal color scheme, which is a JSON object with
+    /// keys such as `black`, `brightPurple` and `background`. Missing colors
+    /// are taken from the xterm palette.
+    pub fn from_windows_terminal(source: &str) -> Result<TerminalPalette, SchemeError> {
+        let mut palette = TerminalPalette::XTERM;
+
+        for (key, value) in json_entries(source)? {
+            palette.set(&key, &value, |name| {
+                let (name, offset) = match name.strip_prefix("bright") {
+                    Some(name) => (name, 8),
+                    None => (name, 0),
+                };
+                let name = match name.to_ascii_lowercase().as_str() {
+                    "purple" => String::from("magenta"),
+                    name => name.to_string(),
+                };
+
+                match name.as_str() {
+                    "foreground" if offset == 0 => Some(16),
+                    "background" if offset == 0 => Some(17),
+                    name => BASE_NAMES
+                        .iter()
+                        .position(|base| *base == name)
+                        .map(|position| position + offset),
+                }
+            })?;
+        }
+
+        Ok(palette)
+    }
+
+    /// Parses the colors of an X resources file, such as `*.color0: #1d1f21`
+    /// or `URxvt.background: rgb:1d/1f/21`, including colors defined with
+    /// `#define`. Missing colors are taken from the xterm palette.
+    pub fn from_xresources(source: &str) -> Result<TerminalPalette, SchemeError> {
+        let mut palette = TerminalPalette::XTERM;
+        let mut defines: Vec<(&str, &str)> = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('!') {
+                continue;
+            }
+
+            if let Some(define) = line.strip_prefix("#define") {
+                let mut words = define.split_whitespace();
+                if let (Some(name), Some(value)) = (words.next(), words.next()) {
+                    defines.push((name, value));
+                }
+                continue;
+            }
+
+            // Other preprocessor directives, such as `#include`.
+            if line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once(':')
+                .ok_or(SchemeError::Syntax { line: index + 1 })?;
+            let value = value.trim();
+            let value = defines
+                .iter()
+                .rev()
+                .find(|(name, _)| *name == value)
+                .map_or(value, |(_, value)| value);
+
+            let key = key.rsplit(['.', '*']).next().unwrap_or_default().trim();
+            palette.set(key, value, color_index)?;
+        }
+
+        Ok(palette)
+    }
+
+    /// Parses the colors of a kitty configuration, such as `color0 #1d1f21`
+    /// and `background #ffffff`. Missing colors are taken from the xterm
+    /// palette.
+    pub fn from_kitty(source: &str) -> Result<TerminalPalette, SchemeError> {
+        let mut palette = TerminalPalette::XTERM;
+
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            if let (Some(key), Some(value)) = (words.next(), words.next()) {
+                palette.set(key, value, color_index)?;
+            }
+        }
+
+        Ok(palette)
+    }
+
+    /// Sets the color for a key, if `index` maps the key to a palette index,
+    /// where 16 is the foreground and 17 is the background.
+    fn set(
+        &mut self,
+        key: &str,
+        value: &str,
+        index: impl Fn(&str) -> Option<usize>,
+    ) -> Result<(), SchemeError> {
+        let Some(index) = index(key) else {
+            return Ok(());
+        };
+
+        let color = parse_value(value).map_err(|error| SchemeError::InvalidColor {
+            key: key.to_string(),
+            error,
+        })?;
+
+        match index {
+            16 => self.foreground = Some(color),
+            17 => self.background = Some(color),
+            index => self.colors[index] = color,
+        }
+
+        Ok(())
+    }
+}
+
+/// Maps the keys `color0` to `color15`, `foreground` and `background` to
+/// palette indexes. See `TerminalPalette::set`.
+#[cfg(feature = "schemes")]
+fn color_index(key: &str) -> Option<usize> {
+    match key {
+        "foreground" => Some(16),
+        "background" => Some(17),
+        key => key
+            .strip_prefix("color")?
+            .parse()
+            .ok()
+            .filter(|index| *index < 16),
+    }
+}
+
+/// Parses a color written as `#1d1f21`, `0x1d1f21` or `rgb:1d/1f/21`,
+/// optionally quoted.
+#[cfg(feature = "schemes")]
+fn parse_value(value: &str) -> Result<Rgb, ParseColorError> {
+    let value = value.trim().trim_matches(['"', '\'']);
+
+    match parse_color_reply(value) {
+        Some(color) => Ok(color),
+        None => Rgb::from_hex(value.strip_prefix("0x").unwrap_or(value)),
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    io::{self, Read, Write},
+    os::fd::AsFd,
+    time::{Duration, Instant},
+};
+
+use rustix::{
+    event::{poll, PollFd, PollFlags},
+    io::Errno,
+};
+
+use crate::color::{parse_color_reply, Rgb};
+
+/// Represents a color that can be queried from the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorQuery {
+    /// The default text color, queried with OSC 10.
+    Foreground,
+    /// The default background color, queried with OSC 11.
+    Background,
+    /// A color of the 256 color palette, queried with OSC 4.
+    Palette(u8),
+}
+
+impl ColorQuery {
+    /// Returns the escape sequence asking the terminal for the color.
+    fn sequence(&self) -> String {
+        match self {
+            ColorQuery::Foreground => String::from("\x1b]10;?\x1b\\"),
+            ColorQuery::Background => String::from("\x1b]11;?\x1b\\"),
+            ColorQuery::Palette(index) => format!("\x1b]4;{index};?\x1b\\"),
+        }
+    }
+
+    /// Returns the start of the payload of the terminal's reply, before the
+    /// color itself.
+    fn prefix(&self) -> String {
+        match self {
+            ColorQuery::Foreground => String::from("10;"),
+            ColorQuery::Background => String::from("11;"),
+            ColorQuery::Palette(index) => format!("4;{index};"),
+        }
+    }
+}
+
+/// Represents an error that occurred while querying the terminal.
+#[derive(Debug)]
+pub enum QueryError {
+    /// Reading from or writing to the terminal failed.
+    Io(io::Error),
+    /// The terminal did not reply before the timeout.
+    Timeout,
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryError::Io(error) => write!(f, "failed to query the terminal: {error}"),
+            QueryError::Timeout => write!(f, "the terminal did not reply in time"),
+        }
+    }
+}
+
+impl Error for QueryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            QueryError::Io(error) => Some(error),
+            QueryError::Timeout => None,
+        }
+    }
+}
+
+impl From<io::Error> for QueryError {
+    fn from(error: io::Error) -> Self {
+        QueryError::Io(error)
+    }
+}
+
+// Asks for the primary device attributes, which virtually every terminal
+// answers. As replies arrive in order, its reply marks the end of the replies
+// to the color queries sent before it.
+const DEVICE_ATTRIBUTES: &str = "\x1b[c";
+
+/// Asks the terminal for the given colors, returning one result per query in
+/// the same order, where `None` means the terminal did not report that color.
+///
+/// `tty` is the terminal, usually `/dev/tty` opened for reading and writing,
+/// which must be in raw mode (or at least have echo and line buffering turned
+/// off) so that the replies are not echoed or held back. Switching modes is
+/// left to the caller.
+///
+/// The terminal is polled until the timeout, so a terminal that does not
+/// answer at all fails with `QueryError::Timeout` instead of blocking, and
+/// nothing is read from it after this returns.
+pub fn query_colors<T>(
+    tty: &mut T,
+    queries: &[ColorQuery],
+    timeout: Duration,
+) -> Result<Vec<Option<Rgb>>, QueryError>
+where
+    T: AsFd + Read + Write,
+{
+    let mut request: String = queries.iter().map(ColorQuery::sequence).collect();
+    request.push_str(DEVICE_ATTRIBUTES);
+
+    tty.write_all(request.as_bytes())?;
+    tty.flush()?;
+
+    let replies = read_replies(tty, timeout)?;
+
+    Ok(queries
+        .iter()
+        .map(|query| {
+            let prefix = query.prefix();
+            replies
+                .iter()
+                .find_map(|reply| reply.strip_prefix(prefix.as_str()))
+                .and_then(parse_color_reply)
+        })
+        .collect())
+}
+
+/// Asks the terminal for its default background color. See `query_colors`.
+pub fn query_background<T>(tty: &mut T, timeout: Duration) -> Result<Option<Rgb>, QueryError>
+where
+    T: AsFd + Read + Write,
+{
+    let colors = query_colors(tty, &[ColorQuery::Background], timeout)?;
+
+    Ok(colors.into_iter().next().flatten())
+}
+
+/// Asks the terminal for its 16 base palette colors. See `query_colors`.
+pub fn query_palette<T>(tty: &mut T, timeout: Duration) -> Result<[Option<Rgb>; 16], QueryError>
+where
+    T: AsFd + Read + Write,
+{
+    let queries: Vec<ColorQuery> = (0..16).map(ColorQuery::Palette).collect();
+    let colors = query_colors(tty, &queries, timeout)?;
+
+    let mut palette = [None; 16];
+    palette.copy_from_slice(&colors);
+
+    Ok(palette)
+}
+
+/// Reads from the terminal until the reply to the device attributes query,
+/// returning the payloads of the OSC replies received before it. Each read
+/// waits for input with `poll`, so that it never blocks past the deadline.
+///
+/// The input is read one byte at a time, so that any keys typed after the
+/// reply are left for the application instead of being consumed with it.
+fn read_replies<T>(tty: &mut T, timeout: Duration) -> Result<Vec<String>, QueryError>
+where
+    T: AsFd + Read,
+{
+    let deadline = Instant::now() + timeout;
+    let mut byte = [0; 1];
+    let mut received = Vec::new();
+
+    let end = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(QueryError::Timeout);
+        }
+
+        // Round up, so that a remaining fraction of a millisecond still waits.
+        let milliseconds = i32::try_from(remaining.as_millis() + 1).unwrap_or(i32::MAX);
+        let mut fds = [PollFd::new(&*tty, PollFlags::IN)];
+        match poll(&mut fds, milliseconds) {
+            Ok(0) => return Err(QueryError::Timeout),
+            Ok(_) => {}
+            Err(Errno::INTR) => continue,
+            Err(error) => return Err(QueryError::Io(error.into())),
+        }
+
+        match tty.read(&mut byte) {
+            // The terminal was closed before replying to everything; use
+            // whatever replies arrived.
+            Ok(0) => break received.len(),
+            Ok(_) => {
+                received.push(byte[0]);
+                if byte[0] == b'c' {
+                    if let Some(end) = device_attributes_end(&received) {
+                        break end;
+                    }
+                }
+            }
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(QueryError::Io(error)),
+        }
+    };
+
+    Ok(osc_payloads(&received[..end]))
+}
+
+/// Returns the position of the first byte after the reply to the device
+/// attributes query, such as `\x1b[?62;22c`, if it was received.
+fn device_attributes_end(bytes: &[u8]) -> Option<usize> {
+    let mut start = 0;
+
+    while let Some(offset) = bytes[start..].windows(3).position(|w| w == b"\x1b[?") {
+        let parameters = start + offset + 3;
+        let length = bytes[parameters..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit() || **byte == b';')
+            .count();
+
+        if bytes.get(parameters + length) == Some(&b'c') {
+            return Some(parameters + length + 1);
+        }
+
+        start = parameters;
+    }
+
+    None
+}
+
+/// Returns the payloads of the OSC sequences in the bytes, which are
+/// terminated by either BEL or ST.
+fn osc_payloads(bytes: &[u8]) -> Vec<String> {
+    let text = String::from_utf8_lossy(bytes);
+    let mut payloads = Vec::new();
+
+    for sequence in text.split("\x1b]").skip(1) {
+        let end = [sequence.find('\x07'), sequence.find("\x1b\\")]
+            .into_iter()
+            .flatten()
+            .min();
+
+        if let Some(end) = end {
+            payloads.push(sequence[..end].to_string());
+        }
+    }
+
+    payloads
+}
@@ -0,0 +1,115 @@
+use std::f32::consts::TAU;
+
+use crate::{
+    color::Rgb, foreground::ForegroundColor, styled_string::StyledString, styled_text::StyledText,
+};
+
+/// Represents a cycling rainbow effect, which colors each character of a
+/// string using offset sine waves for the red, green and blue channels.
+///
+/// The effect can be animated by advancing the phase for each frame; see
+/// `Rainbow::frames`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rainbow {
+    frequency: f32,
+    phase: f32,
+    spread: f32,
+    speed: f32,
+}
+
+impl Default for Rainbow {
+    fn default() -> Self {
+        Self {
+            frequency: 0.1,
+            phase: 0.0,
+            spread: 3.0,
+            speed: 0.1,
+        }
+    }
+}
+
+impl Rainbow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how quickly the colors change, in radians per step. Higher values
+    /// cycle through the rainbow in fewer characters.
+    #[must_use]
+    pub fn frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Sets the starting offset of the first character in the color cycle, in
+    /// radians. A full cycle is `TAU` (2π).
+    #[must_use]
+    pub fn phase(mut self, phase: f32) -> Self {
+        self.phase = phase;
+        self
+    }
+
+    /// Sets how many characters share a single step of the color cycle. Higher
+    /// values stretch the rainbow out over more characters.
+    #[must_use]
+    pub fn spread(mut self, spread: f32) -> Self {
+        self.spread = spread.max(f32::EPSILON);
+        self
+    }
+
+    /// Sets how far the phase advances with each animation frame, in radians.
+    #[must_use]
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Returns the color of the character at the given position.
+    #[must_use]
+    pub fn color_at(&self, index: usize) -> Rgb {
+        let angle = self.frequency * (index as f32 / self.spread) + self.phase;
+        let channel = |offset: f32| ((angle + offset).sin() * 127.0 + 128.0).round() as u8;
+
+        Rgb::new(channel(0.0), channel(TAU / 3.0), channel(2.0 * TAU / 3.0))
+    }
+
+    /// Colors each character of the text using the rainbow. Any modes and the
+    /// background color are kept on every character.
+    pub fn paint(&self, text: impl Into<StyledString>) -> StyledText {
+        let mut index = 0;
+
+        text.into().split_colored(|span, _| {
+            span.foreground = ForegroundColor::Rgb(self.color_at(index));
+            index += 1;
+        })
+    }
+
+    /// Returns an endless iterator of animation frames for the text, with the
+    /// phase advancing by the configured speed on each frame.
+    pub fn frames(&self, text: impl Into<StyledString>) -> Frames {
+        Frames {
+            rainbow: *self,
+            text: text.into(),
+        }
+    }
+}
+
+/// An endless iterator over the animation frames of a `Rainbow`. Created by
+/// `Rainbow::frames`.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct Frames {
+    rainbow: Rainbow,
+    text: StyledString,
+}
+
+impl Iterator for Frames {
+    type Item = StyledText;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.rainbow.paint(self.text.clone());
+        self.rainbow.phase = (self.rainbow.phase + self.rainbow.speed) % TAU;
+
+        Some(frame)
+    }
+}
@@ -0,0 +1,179 @@
+use std::ops::RangeInclusive;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{color::Rgb, color_space::Hsl, distance::Metric};
+
+/// The number of attempts made to find a color which meets every constraint
+/// before falling back to adjusting the last candidate.
+const MAX_ATTEMPTS: usize = 64;
+
+/// The number of candidates compared when picking each distinct color.
+const DISTINCT_CANDIDATES: usize = 32;
+
+/// A generator for random colors within configurable constraints, such as a
+/// range of lightness or a minimum contrast against a background color.
+///
+/// Every method takes a random number generator, so colors can be made
+/// reproducible by passing a seeded generator, or by using the `*_seeded`
+/// methods.
+///
+/// Requires the `random` feature to be enabled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RandomColor {
+    hue: RangeInclusive<f32>,
+    saturation: RangeInclusive<f32>,
+    lightness: RangeInclusive<f32>,
+    contrast: Option<(Rgb, f32)>,
+}
+
+impl Default for RandomColor {
+    fn default() -> Self {
+        Self {
+            hue: 0.0..=360.0,
+            saturation: 0.0..=1.0,
+            lightness: 0.0..=1.0,
+            contrast: None,
+        }
+    }
+}
+
+impl RandomColor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the hue of generated colors, in degrees between 0-360. A
+    /// range which starts after it ends, such as `330.0..=30.0` for reds,
+    /// wraps around 360.
+    #[must_use]
+    pub fn hue(mut self, range: RangeInclusive<f32>) -> Self {
+        self.hue = range;
+        self
+    }
+
+    /// Restricts the HSL saturation of generated colors, between 0.0-1.0.
+    #[must_use]
+    pub fn saturation(mut self, range: RangeInclusive<f32>) -> Self {
+        self.saturation = clamp_range(range);
+        self
+    }
+
+    /// Restricts the HSL lightness of generated colors, between 0.0-1.0.
+    #[must_use]
+    pub fn lightness(mut self, range: RangeInclusive<f32>) -> Self {
+        self.lightness = clamp_range(range);
+        self
+    }
+
+    /// Requires generated colors to have at least the given WCAG contrast ratio
+    /// against a background color, e.g. 4.5 for readable text.
+    #[must_use]
+    pub fn min_contrast(mut self, background: Rgb, ratio: f32) -> Self {
+        self.contrast = Some((background, ratio));
+        self
+    }
+
+    /// Returns a random color which meets the constraints.
+    ///
+    /// If no matching color is found after a number of attempts (e.g. when the
+    /// lightness range rules out the required contrast), the last candidate is
+    /// lightened or darkened until it meets the contrast ratio.
+    pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Rgb {
+        let mut color = self.candidate(rng);
+
+        for _ in 1..MAX_ATTEMPTS {
+            if self.meets_contrast(&color) {
+                return color;
+            }
+
+            color = self.candidate(rng);
+        }
+
+        match self.contrast {
+            Some((background, ratio)) => color.ensure_contrast(&background, ratio),
+            None => color,
+        }
+    }
+
+    /// Returns a random color which meets the constraints, using a generator
+    /// seeded with the given value. The same seed always returns the same
+    /// color for a given version of this library.
+    #[must_use]
+    pub fn generate_seeded(&self, seed: u64) -> Rgb {
+        self.generate(&mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Returns `count` colors which meet the constraints and are as visually
+    /// distinct from each other as possible.
+    ///
+    /// Each color is picked from a set of random candidates, keeping the one
+    /// furthest away (in Oklab) from every color picked before it.
+    pub fn distinct<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> Vec<Rgb> {
+        let mut colors: Vec<Rgb> = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let color = (0..DISTINCT_CANDIDATES)
+                .map(|_| self.generate(rng))
+                .map(|candidate| {
+                    let nearest = colors
+                        .iter()
+                        .map(|color| color.distance(&candidate, Metric::Oklab))
+                        .fold(f32::INFINITY, f32::min);
+
+                    (candidate, nearest)
+                })
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(candidate, _)| candidate);
+
+            colors.extend(color);
+        }
+
+        colors
+    }
+
+    /// Returns `count` distinct colors using a generator seeded with the given
+    /// value. See `RandomColor::distinct` for details.
+    #[must_use]
+    pub fn distinct_seeded(&self, count: usize, seed: u64) -> Vec<Rgb> {
+        self.distinct(count, &mut StdRng::seed_from_u64(seed))
+    }
+
+    fn candidate<R: Rng + ?Sized>(&self, rng: &mut R) -> Rgb {
+        Rgb::from(Hsl::new(
+            sample_hue(rng, &self.hue),
+            sample(rng, &self.saturation),
+            sample(rng, &self.lightness),
+        ))
+    }
+
+    fn meets_contrast(&self, color: &Rgb) -> bool {
+        self.contrast.map_or(true, |(background, ratio)| {
+            color.contrast_ratio(&background) >= ratio
+        })
+    }
+}
+
+fn clamp_range(range: RangeInclusive<f32>) -> RangeInclusive<f32> {
+    range.start().clamp(0.0, 1.0)..=range.end().clamp(0.0, 1.0)
+}
+
+fn sample<R: Rng + ?Sized>(rng: &mut R, range: &RangeInclusive<f32>) -> f32 {
+    let (start, end) = (*range.start(), *range.end());
+
+    if start >= end {
+        start
+    } else {
+        rng.gen_range(start..=end)
+    }
+}
+
+fn sample_hue<R: Rng + ?Sized>(rng: &mut R, range: &RangeInclusive<f32>) -> f32 {
+    let (start, end) = (*range.start(), *range.end());
+
+    if start > end {
+        sample(rng, &(start..=end + 360.0)) % 360.0
+    } else {
+        sample(rng, range)
+    }
+}
@@ -0,0 +1,379 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{
+    adaptive::Appearance,
+    color::{ParseColorError, Rgb},
+    foreground::ForegroundColor,
+    palette::TerminalPalette,
+    style::Style,
+    theme::Theme,
+};
+
+/// Represents an error found while loading a color scheme.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchemeError {
+    /// The file is not valid YAML or JSON, starting at the given line.
+    Syntax { line: usize },
+    /// A color required by the scheme, such as `base0F`, is missing.
+    MissingColor(String),
+    /// A color of the scheme is not a valid hexadecimal color.
+    InvalidColor { key: String, error: ParseColorError },
+}
+
+impl Display for SchemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemeError::Syntax { line } => write!(f, "invalid syntax on line {line}"),
+            SchemeError::MissingColor(key) => write!(f, "missing color `{key}`"),
+            SchemeError::InvalidColor { key, error } => {
+                write!(f, "invalid color `{key}`: {error}")
+            }
+        }
+    }
+}
+
+impl Error for SchemeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SchemeError::InvalidColor { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A base16 or base24 color scheme, as used by hundreds of community themes.
+///
+/// Both the original format, where the colors are top-level keys next to
+/// `scheme`, and the newer format, where they are nested under `palette` next
+/// to `name`, can be read from YAML and JSON files.
+///
+/// Requires the `schemes` feature to be enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scheme {
+    pub name: String,
+    pub author: String,
+    /// The colors `base00` to `base0F`, followed by `base10` to `base17` for
+    /// base24 schemes. It always holds either 16 or 24 colors.
+    palette: Vec<Rgb>,
+}
+
+impl Scheme {
+    /// Creates a scheme from the colors `base00` to `base0F`, followed by
+    /// `base10` to `base17` for base24 schemes. Returns `None` unless the
+    /// palette has exactly 16 or 24 colors.
+    #[must_use]
+    pub fn new(name: &str, author: &str, palette: Vec<Rgb>) -> Option<Scheme> {
+        matches!(palette.len(), 16 | 24).then(|| Scheme {
+            name: name.to_string(),
+            author: author.to_string(),
+            palette,
+        })
+    }
+
+    /// Parses a scheme from either YAML or JSON, depending on whether it
+    /// starts with a `{`.
+    pub fn parse(source: &str) -> Result<Scheme, SchemeError> {
+        if source.trim_start().starts_with('{') {
+            Scheme::from_json(source)
+        } else {
+            Scheme::from_yaml(source)
+        }
+    }
+
+    /// Parses a scheme from YAML. Only the subset of YAML used by scheme
+    /// files is supported: `key: value` pairs, optionally nested by
+    /// indentation, and comments.
+    pub fn from_yaml(source: &str) -> Result<Scheme, SchemeError> {
+        let mut entries = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line == "---" {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once(':')
+                .ok_or(SchemeError::Syntax { line: index + 1 })?;
+            let value = value.trim();
+
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..]
+                    .split_once(quote)
+                    .map(|(value, _)| value)
+                    .ok_or(SchemeError::Syntax { line: index + 1 })?,
+                // An unquoted value ends at a comment.
+                _ => value.split(" #").next().unwrap_or_default().trim(),
+            };
+
+            // Keys without a value start a nested section, such as `palette`,
+            // whose keys are read as if they were at the top level.
+            if !value.is_empty() {
+                entries.push((unquote(key.trim()).to_string(), value.to_string()));
+            }
+        }
+
+        Scheme::from_entries(&entries)
+    }
+
+    /// Parses a scheme from JSON. Nested objects, such as `palette`, are read
+    /// as if their keys were at the top level.
+    pub fn from_json(source: &str) -> Result<Scheme, SchemeError> {
+        Scheme::from_entries(&json_entries(source)?)
+    }
+
+    /// Builds a scheme from its flattened `key: value` pairs.
+    fn from_entries(entries: &[(String, String)]) -> Result<Scheme, SchemeError> {
+        let find = |key: &str| {
+            entries
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(_, value)| value.clone())
+        };
+
+        let count = if find("base10").is_some() { 24 } else { 16 };
+        let palette = (0..count)
+            .map(|index| {
+                let key = format!("base{index:02X}");
+                let value = find(&key).ok_or_else(|| SchemeError::MissingColor(key.clone()))?;
+
+                Rgb::from_hex(&value).map_err(|error| SchemeError::InvalidColor { key, error })
+            })
+            .collect::<Result<Vec<Rgb>, SchemeError>>()?;
+
+        Ok(Scheme {
+            name: find("name").or_else(|| find("scheme")).unwrap_or_default(),
+            author: find("author").unwrap_or_default(),
+            palette,
+        })
+    }
+
+    /// Returns the colors of the scheme, which are either the 16 colors of a
+    /// base16 scheme or the 24 colors of a base24 scheme.
+    #[must_use]
+    pub fn palette(&self) -> &[Rgb] {
+        &self.palette
+    }
+
+    /// Returns the color `base00` to `base17` at the given index, or `None`
+    /// if the scheme does not have it.
+    #[must_use]
+    pub fn base(&self, index: usize) -> Option<Rgb> {
+        self.palette.get(index).copied()
+    }
+
+    /// Returns whether this is a base24 scheme, with 24 colors.
+    #[must_use]
+    pub fn is_base24(&self) -> bool {
+        self.palette.len() == 24
+    }
+
+    /// Returns whether the scheme has a light or a dark background, `base00`.
+    #[must_use]
+    pub fn appearance(&self) -> Appearance {
+        Appearance::from_background(self.palette[0])
+    }
+
+    /// Returns the terminal palette used by the scheme, following the mapping
+    /// of base16-shell: `base00` and `base05` are the background and
+    /// foreground, and the base colors use the matching accent colors.
+    #[must_use]
+    pub fn terminal_palette(&self) -> TerminalPalette {
+        let base = |index: usize| self.palette[index];
+        let accents = [0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C];
+
+        let mut colors = [base(0x00); 16];
+        for (position, accent) in accents.into_iter().enumerate() {
+            colors[position + 1] = base(accent);
+            colors[position + 9] = base(accent);
+        }
+        colors[7] = base(0x05);
+        colors[8] = base(0x03);
+        colors[15] = base(0x07);
+
+        TerminalPalette {
+            colors,
+            foreground: Some(base(0x05)),
+            background: Some(base(0x00)),
+        }
+    }
+
+    /// Returns a theme using the colors of the scheme, following the base16
+    /// styling guidelines: red (`base08`) for errors, yellow (`base0A`) for
+    /// warnings, green (`base0B`) for success, blue (`base0D`) for information,
+    /// the comment color (`base03`) for muted text and cyan (`base0C`) for
+    /// accents.
+    #[must_use]
+    pub fn theme(&self) -> Theme {
+        let color = |index: usize| ForegroundColor::Rgb(self.palette[index]);
+
+        Theme {
+            error: Style::new().bold().with_foreground(color(0x08)),
+            warning: Style::new().bold().with_foreground(color(0x0A)),
+            success: Style::new().with_foreground(color(0x0B)),
+            info: Style::new().with_foreground(color(0x0D)),
+            muted: Style::new().with_foreground(color(0x03)),
+            accent: Style::new().bold().with_foreground(color(0x0C)),
+        }
+    }
+}
+
+/// Removes the quotes around a YAML key, if any.
+fn unquote(key: &str) -> &str {
+    key.strip_prefix('"')
+        .and_then(|key| key.strip_suffix('"'))
+        .or_else(|| {
+            key.strip_prefix('\'')
+                .and_then(|key| key.strip_suffix('\''))
+        })
+        .unwrap_or(key)
+}
+
+/// Parses JSON into the string values it contains, along with their keys,
+/// flattening nested objects.
+pub(crate) fn json_entries(source: &str) -> Result<Vec<(String, String)>, SchemeError> {
+    let mut parser = JsonParser {
+        source,
+        position: 0,
+        entries: Vec::new(),
+    };
+
+    parser
+        .value(None)
+        .and_then(|()| {
+            parser.whitespace();
+            (parser.position == source.len()).then_some(())
+        })
+        .ok_or_else(|| SchemeError::Syntax {
+            line: source[..parser.position.min(source.len())]
+                .matches('\n')
+                .count()
+                + 1,
+        })?;
+
+    Ok(parser.entries)
+}
+
+/// A minimal JSON parser, which records every string value along with its key
+/// while skipping over everything else.
+struct JsonParser<'a> {
+    source: &'a str,
+    position: usize,
+    entries: Vec<(String, String)>,
+}
+
+impl JsonParser<'_> {
+    fn whitespace(&mut self) {
+        while self
+            .source
+            .as_bytes()
+            .get(self.position)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.position += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) -> Option<()> {
+        self.whitespace();
+        (self.source.as_bytes().get(self.position) == Some(&byte)).then(|| self.position += 1)
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.whitespace();
+        self.source.as_bytes().get(self.position).copied()
+    }
+
+    /// Parses a value, recording it under `key` if it is a string.
+    fn value(&mut self, key: Option<&str>) -> Option<()> {
+        match self.peek()? {
+            b'{' => self.sequence(b'{', b'}', |parser| {
+                let key = parser.string()?;
+                parser.eat(b':')?;
+                parser.value(Some(&key))
+            }),
+            b'[' => self.sequence(b'[', b']', |parser| parser.value(None)),
+            b'"' => {
+                let value = self.string()?;
+                if let Some(key) = key {
+                    self.entries.push((key.to_string(), value));
+                }
+                Some(())
+            }
+            _ => {
+                let start = self.position;
+                while self
+                    .source
+                    .as_bytes()
+                    .get(self.position)
+                    .is_some_and(|byte| byte.is_ascii_alphanumeric() || b"+-.".contains(byte))
+                {
+                    self.position += 1;
+                }
+
+                (self.position > start).then_some(())
+            }
+        }
+    }
+
+    /// Parses a comma separated sequence of items between two delimiters.
+    fn sequence(
+        &mut self,
+        open: u8,
+        close: u8,
+        mut item: impl FnMut(&mut Self) -> Option<()>,
+    ) -> Option<()> {
+        self.eat(open)?;
+        if self.eat(close).is_some() {
+            return Some(());
+        }
+
+        loop {
+            item(self)?;
+            if self.eat(close).is_some() {
+                return Some(());
+            }
+            self.eat(b',')?;
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.eat(b'"')?;
+        let mut value = String::new();
+
+        loop {
+            let c = self.source[self.position..].chars().next()?;
+            self.position += c.len_utf8();
+
+            match c {
+                '"' => return Some(value),
+                '\\' => {
+                    let escape = self.source[self.position..].chars().next()?;
+                    self.position += escape.len_utf8();
+
+                    value.push(match escape {
+                        '"' | '\\' | '/' => escape,
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'u' => {
+                            let digits = self.source.get(self.position..self.position + 4)?;
+                            // `from_str_radix` also accepts a leading sign.
+                            if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                                return None;
+                            }
+                            self.position += 4;
+                            let code = u32::from_str_radix(digits, 16).ok()?;
+                            char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        // Any other escape is invalid JSON.
+                        _ => return None,
+                    });
+                }
+                c => value.push(c),
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt::Formatter;
+
+use serde::{
+    de::{self, MapAccess, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    adaptive::Adaptive,
+    background::BackgroundColor,
+    color::{Rgb, Simple},
+    foreground::ForegroundColor,
+    style::{mode_name, parse_color, parse_mode, parse_negated_mode, to_background, Style},
+};
+
+/// Serializes as a hexadecimal string, such as `"#ff8800"`.
+impl Serialize for Rgb {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Rgb {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RgbVisitor;
+
+        impl<'de> Visitor<'de> for RgbVisitor {
+            type Value = Rgb;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "a hexadecimal color such as \"#ff8800\"")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Rgb, E> {
+                Rgb::from_hex(value).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(RgbVisitor)
+    }
+}
+
+/// Serializes `Empty` as nothing, named colors by name (`"red"`), other
+/// indexed colors as integers, `Rgb` colors as hexadecimal strings, and
+/// `Adaptive` colors as a `light` and `dark` pair.
+impl Serialize for ForegroundColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ForegroundColor::Empty => serializer.serialize_none(),
+            ForegroundColor::Simple(color) => match Simple(*color).name() {
+                Some(name) => serializer.serialize_str(name),
+                None => serializer.serialize_u8(*color),
+            },
+            ForegroundColor::Indexed(index) => serializer.serialize_u8(*index),
+            ForegroundColor::Rgb(color) => color.serialize(serializer),
+            ForegroundColor::Adaptive(color) => {
+                let mut state = serializer.serialize_struct("Adaptive", 2)?;
+                state.serialize_field("light", &color.light)?;
+                state.serialize_field("dark", &color.dark)?;
+                state.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ForegroundColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ColorVisitor)
+    }
+}
+
+/// Serializes the same way as `ForegroundColor`.
+impl Serialize for BackgroundColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let color = match *self {
+            BackgroundColor::Empty => ForegroundColor::Empty,
+            BackgroundColor::Simple(color) => ForegroundColor::Simple(color),
+            BackgroundColor::Indexed(index) => ForegroundColor::Indexed(index),
+            BackgroundColor::Rgb(color) => ForegroundColor::Rgb(color),
+            BackgroundColor::Adaptive(color) => ForegroundColor::Adaptive(color),
+        };
+
+        color.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BackgroundColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(ColorVisitor)
+            .map(to_background)
+    }
+}
+
+/// Accepts any of the forms a color is serialized as, along with color
+/// indexes written as strings.
+struct ColorVisitor;
+
+impl<'de> Visitor<'de> for ColorVisitor {
+    type Value = ForegroundColor;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "a color name, a color index, a hexadecimal color or a light and dark pair"
+        )
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(ForegroundColor::Empty)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(ForegroundColor::Empty)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        u8::try_from(value)
+            .map(ForegroundColor::indexed)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        u8::try_from(value)
+            .map(ForegroundColor::indexed)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        if value.starts_with('#') {
+            return Rgb::from_hex(value)
+                .map(ForegroundColor::Rgb)
+                .map_err(E::custom);
+        }
+
+        parse_color(value).ok_or_else(|| E::custom(format!("unknown color `{value}`")))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut light = None;
+        let mut dark = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "light" => light = Some(map.next_value::<Rgb>()?),
+                "dark" => dark = Some(map.next_value::<Rgb>()?),
+                key => return Err(de::Error::unknown_field(key, &["light", "dark"])),
+            }
+        }
+
+        Ok(ForegroundColor::Adaptive(Adaptive::new(
+            light.ok_or_else(|| de::Error::missing_field("light"))?,
+            dark.ok_or_else(|| de::Error::missing_field("dark"))?,
+        )))
+    }
+}
+
+/// Serializes as a struct with optional `foreground` and `background` colors,
+/// and a list of `modes`, such as `["bold", "no-italic"]`.
+impl Serialize for Style {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Style", 3)?;
+
+        if self.foreground == ForegroundColor::Empty {
+            state.skip_field("foreground")?;
+        } else {
+            state.serialize_field("foreground", &self.foreground)?;
+        }
+
+        if self.background == BackgroundColor::Empty {
+            state.skip_field("background")?;
+        } else {
+            state.serialize_field("background", &self.background)?;
+        }
+
+        let mut modes: Vec<String> = self
+            .mode_codes()
+            .filter_map(mode_name)
+            .map(String::from)
+            .collect();
+        modes.extend(
+            self.disabled_codes()
+                .filter_map(mode_name)
+                .map(|name| format!("no-{name}")),
+        );
+        state.serialize_field("modes", &modes)?;
+        state.end()
+    }
+}
+
+/// Deserializes from either the struct form, where every field is optional,
+/// or a string of space separated words such as `"bold red on #202020"`.
+impl<'de> Deserialize<'de> for Style {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StyleVisitor;
+
+        impl<'de> Visitor<'de> for StyleVisitor {
+            type Value = Style;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "a style such as \"bold red on #202020\" or a struct")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Style, E> {
+                value.parse().map_err(E::custom)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Style, A::Error> {
+                let mut style = Style::new();
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "foreground" | "fg" => style.foreground = map.next_value()?,
+                        "background" | "bg" => style.background = map.next_value()?,
+                        "modes" => {
+                            for name in map.next_value::<Vec<String>>()? {
+                                if let Some(mode) = parse_mode(&name) {
+                                    style = style.mode(mode);
+                                } else if let Some(mode) = parse_negated_mode(&name) {
+                                    style = style.disable(mode);
+                                } else {
+                                    return Err(de::Error::custom(format!(
+                                        "unknown mode `{name}`"
+                                    )));
+                                }
+                            }
+                        }
+                        key => {
+                            return Err(de::Error::unknown_field(
+                                key,
+                                &["foreground", "background", "modes"],
+                            ))
+                        }
+                    }
+                }
+
+                Ok(style)
+            }
+        }
+
+        deserializer.deserialize_any(StyleVisitor)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    background::BackgroundColor,
+    color::Rgb,
+    constants::{BLACK, BOLD, DIM, WHITE},
+    foreground::ForegroundColor,
+    style::{reset_code, to_background, Style},
+};
+
+impl Style {
+    /// Parses a style from SGR parameters, the part of an escape sequence
+    /// between `\x1b[` and `m`, such as `"01;38;5;208"`. This is the format
+    /// used by `LS_COLORS`, `GREP_COLORS` and similar settings.
+    ///
+    /// Returns `None` if a parameter is not a number, or an extended color is
+    /// incomplete. Unsupported parameters, such as fonts, are ignored.
+    #[must_use]
+    pub fn from_sgr(parameters: &str) -> Option<Style> {
+        Style::new().with_sgr(parameters)
+    }
+
+    /// Returns this style with SGR parameters applied on top of it, the way a
+    /// terminal updates its current style. See `Style::from_sgr`.
+    pub(crate) fn with_sgr(self, parameters: &str) -> Option<Style> {
+        let codes = parameters
+            .split(';')
+            .map(|code| match code.trim() {
+                // An empty parameter means 0, such as in `\x1b[m`.
+                "" => Some(0),
+                code => code.parse::<u8>().ok(),
+            })
+            .collect::<Option<Vec<u8>>>()?;
+
+        let mut style = self;
+        let mut codes = codes.into_iter();
+
+        while let Some(code) = codes.next() {
+            match code {
+                0 => style = Style::new(),
+                1..=9 if code != 6 => style = style.mode(code),
+                22 => style = style.disable(BOLD).disable(DIM),
+                23..=29 if code != 26 => style = style.disable(code - 20),
+                BLACK..=WHITE => style.foreground = ForegroundColor::Simple(code),
+                38 => style.foreground = extended_color(&mut codes)?,
+                39 => style.foreground = ForegroundColor::Empty,
+                40..=47 => style.background = BackgroundColor::Simple(code - 10),
+                48 => style.background = to_background(extended_color(&mut codes)?),
+                49 => style.background = BackgroundColor::Empty,
+                90..=97 => style.foreground = ForegroundColor::Simple(code - 90 + 8),
+                100..=107 => style.background = BackgroundColor::Simple(code - 100 + 8),
+                _ => {}
+            }
+        }
+
+        Some(style)
+    }
+
+    /// Returns the SGR parameters of the style, such as `"1;38;5;208"`, which
+    /// can be parsed back with `Style::from_sgr`. Modes the style turns off
+    /// are written as their reset codes, and `Adaptive` colors are resolved.
+    #[must_use]
+    pub fn to_sgr(&self) -> String {
+        let mut codes: Vec<u8> = self.mode_codes().collect();
+        codes.extend(self.disabled_codes().map(reset_code));
+
+        // The bright colors use their 16 color codes, as these settings are
+        // often written for terminals without 256 colors.
+        match self.foreground.resolved() {
+            ForegroundColor::Simple(code @ BLACK..=WHITE) => codes.push(code),
+            ForegroundColor::Simple(index @ 8..=15) => codes.push(index - 8 + 90),
+            ForegroundColor::Simple(index) | ForegroundColor::Indexed(index) => {
+                codes.extend([38, 5, index]);
+            }
+            ForegroundColor::Rgb(rgb) => codes.extend([38, 2, rgb.red, rgb.green, rgb.blue]),
+            ForegroundColor::Empty | ForegroundColor::Adaptive(_) => {}
+        }
+
+        match self.background.resolved() {
+            BackgroundColor::Simple(code @ BLACK..=WHITE) => codes.push(code + 10),
+            BackgroundColor::Simple(index @ 8..=15) => codes.push(index - 8 + 100),
+            BackgroundColor::Simple(index) | BackgroundColor::Indexed(index) => {
+                codes.extend([48, 5, index]);
+            }
+            BackgroundColor::Rgb(rgb) => codes.extend([48, 2, rgb.red, rgb.green, rgb.blue]),
+            BackgroundColor::Empty | BackgroundColor::Adaptive(_) => {}
+        }
+
+        codes
+            .iter()
+            .map(u8::to_string)
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+/// Parses the rest of an extended color, after 38 or 48: either `5;index`,
+/// which keeps the palette index, or `2;red;green;blue`.
+fn extended_color(codes: &mut impl Iterator<Item = u8>) -> Option<ForegroundColor> {
+    match codes.next()? {
+        5 => Some(ForegroundColor::indexed(codes.next()?)),
+        2 => Some(ForegroundColor::Rgb(Rgb::new(
+            codes.next()?,
+            codes.next()?,
+            codes.next()?,
+        ))),
+        _ => None,
+    }
+}
@@ -0,0 +1,457 @@
+use std::{error::Error, fmt::Display, hash::Hash, str::FromStr};
+
+use iridescent_grammar as grammar;
+pub(crate) use iridescent_grammar::parse_mode;
+
+use crate::{
+    adaptive::Adaptive,
+    background::BackgroundColor,
+    color::{Rgb, Simple},
+    constants::{BLINK, BOLD, DIM, HIDDEN, INVERT, ITALIC, STRIKE, UNDERLINE},
+    foreground::ForegroundColor,
+    styled_string::StyledString,
+};
+
+/// Represents a reusable set of modes and colors, without any text. A `Style`
+/// can be applied to any number of strings, which makes it a convenient way
+/// to define the look of an application in a single place.
+///
+/// Styles can be declared as `const` items using `Style::new`, the mode
+/// methods, `Style::with_foreground` and `Style::with_background`.
+///
+/// A style can also be parsed from, and displayed as, a string of words such
+/// as `"bold italic #ff0 on blue"`: modes, negated modes such as `no-bold`, a
+/// text color, and a background color after `on`, in any order. Colors are
+/// names, 8-bit indexes, hexadecimal values, `normal` for no color, or
+/// `#light/#dark` for an `Adaptive` color. Like in git's `color.*` settings, a
+/// second color without `on` is also the background color.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    // Each mode is stored as a bit, indexed by its SGR code.
+    pub(crate) modes: u16,
+    // Modes which are explicitly turned off, stored the same way.
+    pub(crate) disabled: u16,
+    pub(crate) foreground: ForegroundColor,
+    pub(crate) background: BackgroundColor,
+}
+
+impl Style {
+    pub const fn new() -> Self {
+        Self {
+            modes: 0,
+            disabled: 0,
+            foreground: ForegroundColor::Empty,
+            background: BackgroundColor::Empty,
+        }
+    }
+
+    /// Returns a style with a foreground color derived from the key, which is
+    /// the same for every run and on every platform. See `Rgb::from_hash`.
+    pub fn for_key<K: Hash + ?Sized>(key: &K) -> Self {
+        Self::new().foreground(Rgb::from_hash(key, &[]))
+    }
+
+    /// Sets the text color.
+    #[must_use]
+    pub fn foreground(mut self, color: impl Into<ForegroundColor>) -> Self {
+        self.foreground = color.into();
+        self
+    }
+
+    /// Sets the background color.
+    #[must_use]
+    pub fn background(mut self, color: impl Into<BackgroundColor>) -> Self {
+        self.background = color.into();
+        self
+    }
+
+    /// Sets the text color. Unlike `Style::foreground`, this can be used to
+    /// declare `const` styles.
+    #[must_use]
+    pub const fn with_foreground(mut self, color: ForegroundColor) -> Self {
+        self.foreground = color;
+        self
+    }
+
+    /// Sets the background color. Unlike `Style::background`, this can be used
+    /// to declare `const` styles.
+    #[must_use]
+    pub const fn with_background(mut self, color: BackgroundColor) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// Applies the bold attribute.
+    #[must_use]
+    pub const fn bold(self) -> Self {
+        self.mode(BOLD)
+    }
+
+    /// Applies the dim attribute.
+    #[must_use]
+    pub const fn dim(self) -> Self {
+        self.mode(DIM)
+    }
+
+    /// Applies the italic attribute.
+    #[must_use]
+    pub const fn italic(self) -> Self {
+        self.mode(ITALIC)
+    }
+
+    /// Applies the underline attribute.
+    #[must_use]
+    pub const fn underline(self) -> Self {
+        self.mode(UNDERLINE)
+    }
+
+    /// Applies the blink attribute.
+    #[must_use]
+    pub const fn blink(self) -> Self {
+        self.mode(BLINK)
+    }
+
+    /// Inverts the foreground and background colors.
+    #[must_use]
+    pub const fn invert(self) -> Self {
+        self.mode(INVERT)
+    }
+
+    /// Applies the hidden attribute.
+    #[must_use]
+    pub const fn hidden(self) -> Self {
+        self.mode(HIDDEN)
+    }
+
+    /// Applies the strike-through attribute.
+    #[must_use]
+    pub const fn strike(self) -> Self {
+        self.mode(STRIKE)
+    }
+
+    /// Returns the text color.
+    #[must_use]
+    pub const fn foreground_color(&self) -> ForegroundColor {
+        self.foreground
+    }
+
+    /// Returns the background color.
+    #[must_use]
+    pub const fn background_color(&self) -> BackgroundColor {
+        self.background
+    }
+
+    /// Returns whether the given mode (e.g. `BOLD`) is applied.
+    #[must_use]
+    pub const fn has_mode(&self, mode: u8) -> bool {
+        mode < 16 && self.modes & (1 << mode) != 0
+    }
+
+    /// Explicitly turns off the given mode (e.g. `BOLD`), which removes it
+    /// when this style is applied or layered on top of another style. In
+    /// `Style::to_sgr`, the mode is turned off with its reset code, such as 22
+    /// for `BOLD`. Modes with codes of 16 and above are not supported, and are
+    /// ignored.
+    #[must_use]
+    pub const fn disable(mut self, mode: u8) -> Self {
+        if mode >= 16 {
+            return self;
+        }
+
+        self.modes &= !(1 << mode);
+        self.disabled |= 1 << mode;
+        self
+    }
+
+    /// Returns whether the given mode (e.g. `BOLD`) is explicitly turned off.
+    #[must_use]
+    pub const fn disables_mode(&self, mode: u8) -> bool {
+        mode < 16 && self.disabled & (1 << mode) != 0
+    }
+
+    /// Returns the applied modes, ordered by their SGR code.
+    pub(crate) fn mode_codes(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..16).filter(|mode| self.has_mode(*mode))
+    }
+
+    /// Returns the modes which are turned off, ordered by their SGR code.
+    pub(crate) fn disabled_codes(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..16).filter(|mode| self.disables_mode(*mode))
+    }
+
+    /// Returns this style with another style layered on top of it. The modes of
+    /// both styles are combined, except for those the other style turns off,
+    /// and the colors of the other style replace the colors of this style if
+    /// they are set.
+    #[must_use]
+    pub const fn patch(mut self, other: Style) -> Self {
+        self.modes = (self.modes & !other.disabled) | other.modes;
+        self.disabled = (self.disabled & !other.modes) | other.disabled;
+
+        if !matches!(other.foreground, ForegroundColor::Empty) {
+            self.foreground = other.foreground;
+        }
+
+        if !matches!(other.background, BackgroundColor::Empty) {
+            self.background = other.background;
+        }
+
+        self
+    }
+
+    /// Applies the style to the text.
+    pub fn apply(&self, text: impl AsRef<str>) -> StyledString {
+        StyledString::new(text).style(*self)
+    }
+
+    /// Applies a mode by its SGR code, ignoring codes of 16 and above, which
+    /// do not fit in the bit sets.
+    pub(crate) const fn mode(mut self, mode: u8) -> Self {
+        if mode >= 16 {
+            return self;
+        }
+
+        self.modes |= 1 << mode;
+        self.disabled &= !(1 << mode);
+        self
+    }
+}
+
+/// Represents an error found while parsing a style from a string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseStyleError {
+    /// A word was not a mode, a negated mode or a color.
+    UnknownWord(String),
+    /// The string ended with `on`, without a background color.
+    MissingBackground,
+    /// More than two colors were given.
+    TooManyColors(String),
+}
+
+impl Display for ParseStyleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseStyleError::UnknownWord(word) => write!(f, "unknown style `{word}`"),
+            ParseStyleError::MissingBackground => {
+                write!(f, "expected a background color after `on`")
+            }
+            ParseStyleError::TooManyColors(word) => {
+                write!(
+                    f,
+                    "unexpected color `{word}`, a style has at most two colors"
+                )
+            }
+        }
+    }
+}
+
+impl Error for ParseStyleError {}
+
+impl FromStr for Style {
+    type Err = ParseStyleError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut style = Style::new();
+        let mut has_foreground = false;
+        let mut has_background = false;
+        let mut words = spec.split_whitespace();
+
+        while let Some(word) = words.next() {
+            if let Some(mode) = parse_mode(word) {
+                style = style.mode(mode);
+            } else if let Some(mode) = parse_negated_mode(word) {
+                style = style.disable(mode);
+            } else if word.eq_ignore_ascii_case("on") {
+                let color = words.next().ok_or(ParseStyleError::MissingBackground)?;
+                if has_background {
+                    return Err(ParseStyleError::TooManyColors(color.to_string()));
+                }
+
+                style.background = to_background(
+                    parse_style_color(color)
+                        .ok_or_else(|| ParseStyleError::UnknownWord(color.to_string()))?,
+                );
+                has_background = true;
+            } else {
+                let color = parse_style_color(word)
+                    .ok_or_else(|| ParseStyleError::UnknownWord(word.to_string()))?;
+
+                // A color is the foreground, unless it was already given, in
+                // which case it is the background.
+                if !has_foreground {
+                    style.foreground = color;
+                    has_foreground = true;
+                } else if !has_background {
+                    style.background = to_background(color);
+                    has_background = true;
+                } else {
+                    return Err(ParseStyleError::TooManyColors(word.to_string()));
+                }
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+/// Displays the style as words which can be parsed back into the same style,
+/// such as `"bold no-italic red on #202020"`.
+impl Display for Style {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut words: Vec<String> = self
+            .mode_codes()
+            .filter_map(mode_name)
+            .map(String::from)
+            .collect();
+
+        words.extend(
+            self.disabled_codes()
+                .filter_map(mode_name)
+                .map(|name| format!("no-{name}")),
+        );
+
+        let background = match self.background {
+            BackgroundColor::Empty => None,
+            BackgroundColor::Simple(color) => Some(ForegroundColor::Simple(color)),
+            BackgroundColor::Indexed(index) => Some(ForegroundColor::Indexed(index)),
+            BackgroundColor::Rgb(color) => Some(ForegroundColor::Rgb(color)),
+            BackgroundColor::Adaptive(color) => Some(ForegroundColor::Adaptive(color)),
+        };
+
+        if self.foreground != ForegroundColor::Empty {
+            words.push(color_word(self.foreground));
+        } else if background.is_some() {
+            words.push(String::from("normal"));
+        }
+
+        if let Some(background) = background {
+            words.push(String::from("on"));
+            words.push(color_word(background));
+        }
+
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+/// Returns the word for a color in the string form of a style.
+fn color_word(color: ForegroundColor) -> String {
+    match color {
+        ForegroundColor::Empty => String::from("normal"),
+        ForegroundColor::Simple(color) => match Simple(color).name() {
+            Some(name) => name.to_string(),
+            None => color.to_string(),
+        },
+        ForegroundColor::Indexed(index) => index.to_string(),
+        ForegroundColor::Rgb(color) => color.to_hex(),
+        ForegroundColor::Adaptive(color) => {
+            format!("{}/{}", color.light.to_hex(), color.dark.to_hex())
+        }
+    }
+}
+
+/// Parses a color in the string form of a style, which besides the colors
+/// accepted by `parse_color` can be `normal` (or `default`) for no color, or
+/// an adaptive `#light/#dark` pair.
+fn parse_style_color(word: &str) -> Option<ForegroundColor> {
+    if word.eq_ignore_ascii_case("normal") || word.eq_ignore_ascii_case("default") {
+        return Some(ForegroundColor::Empty);
+    }
+
+    if let Some((light, dark)) = word.split_once('/') {
+        return Some(ForegroundColor::Adaptive(Adaptive::new(
+            Rgb::parse_hex(light)?,
+            Rgb::parse_hex(dark)?,
+        )));
+    }
+
+    parse_color(word)
+}
+
+/// Parses a negated mode, such as `"no-bold"` or `"nobold"`.
+pub(crate) fn parse_negated_mode(word: &str) -> Option<u8> {
+    let lowercase = word.to_ascii_lowercase();
+    let name = lowercase.strip_prefix("no")?;
+
+    parse_mode(name.strip_prefix('-').unwrap_or(name))
+}
+
+/// Returns the SGR code which turns off a mode, such as 22 for `BOLD`.
+pub(crate) const fn reset_code(mode: u8) -> u8 {
+    // 21 is double underline rather than "not bold" in most terminals.
+    if mode == BOLD {
+        22
+    } else {
+        mode + 20
+    }
+}
+
+/// Returns the name of a mode, such as `"bold"` for `BOLD`.
+pub(crate) fn mode_name(mode: u8) -> Option<&'static str> {
+    let name = match mode {
+        BOLD => "bold",
+        DIM => "dim",
+        ITALIC => "italic",
+        UNDERLINE => "underline",
+        BLINK => "blink",
+        INVERT => "invert",
+        HIDDEN => "hidden",
+        STRIKE => "strike",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Parses a color, which can be a name (`"red"`), a hexadecimal value
+/// (`"#ff8800"`) or an index of the 256 color palette (`"208"`).
+pub(crate) fn parse_color(word: &str) -> Option<ForegroundColor> {
+    grammar::parse_color(word).map(from_grammar)
+}
+
+/// Converts a color of the shared style grammar into a foreground color.
+pub(crate) const fn from_grammar(color: grammar::Color) -> ForegroundColor {
+    match color {
+        grammar::Color::Simple(code) => ForegroundColor::Simple(code),
+        grammar::Color::Index(index) => ForegroundColor::indexed(index),
+        grammar::Color::Rgb(red, green, blue) => ForegroundColor::Rgb(Rgb::new(red, green, blue)),
+    }
+}
+
+/// Converts a parsed color into a background color.
+pub(crate) fn to_background(color: ForegroundColor) -> BackgroundColor {
+    match color {
+        ForegroundColor::Empty => BackgroundColor::Empty,
+        ForegroundColor::Simple(color) => BackgroundColor::Simple(color),
+        ForegroundColor::Indexed(index) => BackgroundColor::Indexed(index),
+        ForegroundColor::Rgb(color) => BackgroundColor::Rgb(color),
+        ForegroundColor::Adaptive(color) => BackgroundColor::Adaptive(color),
+    }
+}
+
+impl StyledString {
+    /// Applies a `Style` on top of the string. Its modes are added to the
+    /// existing ones, modes it turns off are removed, and its colors replace
+    /// the existing ones if set.
+    pub fn style(mut self, style: Style) -> Self {
+        // The string starts from a reset, so a mode that is turned off only
+        // needs to be left out. Reset codes could turn off other modes too,
+        // as 22 turns off both bold and dim.
+        self.modes.retain(|code| !style.disables_mode(*code));
+
+        for mode in style.mode_codes() {
+            if !self.modes.contains(&mode) {
+                self.modes.push(mode);
+            }
+        }
+
+        if style.foreground != ForegroundColor::Empty {
+            self.foreground = style.foreground;
+        }
+
+        if style.background != BackgroundColor::Empty {
+            self.background = style.background;
+        }
+
+        self
+    }
+}
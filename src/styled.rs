@@ -1,8 +1,12 @@
 use crate::{
     background::BackgroundColor,
+    color::Rgb,
     constants::{BLACK, BLUE, CYAN, GREEN, MAGENTA, RED, WHITE, YELLOW},
     foreground::ForegroundColor,
-    StyledString,
+    gradient::Interpolation,
+    rainbow::Rainbow,
+    style::Style,
+    StyledString, StyledText,
 };
 
 /// Implements all the `StyledString` functions on any type `AsRef<str>`.
@@ -11,10 +15,35 @@ use crate::{
 pub trait Styled {
     fn to_styled_string(&self) -> StyledString;
 
+    /// Applies a `Style` to the string.
+    fn style(&self, style: Style) -> StyledString {
+        self.to_styled_string().style(style)
+    }
+
     // Various color types.
     fn foreground(&self, color: impl Into<ForegroundColor>) -> StyledString;
     fn background(&self, color: impl Into<BackgroundColor>) -> StyledString;
 
+    /// Colors each character of the string along a gradient of evenly spaced
+    /// color stops, blended in the Oklab color space.
+    fn gradient(&self, stops: &[Rgb]) -> StyledText {
+        self.to_styled_string()
+            .gradient(stops, Interpolation::Oklab)
+    }
+
+    /// Colors the background behind each character of the string along a
+    /// gradient of evenly spaced color stops, blended in the Oklab color space.
+    fn background_gradient(&self, stops: &[Rgb]) -> StyledText {
+        self.to_styled_string()
+            .background_gradient(stops, Interpolation::Oklab)
+    }
+
+    /// Colors each character of the string using a `Rainbow` with the default
+    /// settings.
+    fn rainbow(&self) -> StyledText {
+        Rainbow::default().paint(self.to_styled_string())
+    }
+
     // Text modes.
     fn bold(&self) -> StyledString;
     fn dim(&self) -> StyledString;
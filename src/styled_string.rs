@@ -7,6 +7,7 @@ use crate::{
         INVERT, ITALIC, LOW_DEPTH, MAGENTA, RED, STRIKE, UNDERLINE, WHITE, YELLOW,
     },
     foreground::ForegroundColor,
+    vision::preview,
 };
 
 /// Represents a string with internal data for the ANSI escape sequences, so it
@@ -16,10 +17,10 @@ use crate::{
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[must_use]
 pub struct StyledString {
-    text: String,
-    modes: Vec<u8>,
-    foreground: ForegroundColor,
-    background: BackgroundColor,
+    pub(crate) text: String,
+    pub(crate) modes: Vec<u8>,
+    pub(crate) foreground: ForegroundColor,
+    pub(crate) background: BackgroundColor,
 }
 
 impl Default for StyledString {
@@ -148,8 +149,21 @@ impl Display for StyledString {
             sequence.push(*mode);
         }
 
+        // Adaptive colors are resolved against the terminal background, and
+        // when previewing a color vision deficiency, the colors are swapped
+        // for their simulated versions right before rendering.
+        let foreground = self.foreground.resolved();
+        let background = self.background.resolved();
+        let (foreground, background) = match preview() {
+            Some(deficiency) => (
+                foreground.simulate(deficiency),
+                background.simulate(deficiency),
+            ),
+            None => (foreground, background),
+        };
+
         // Colors come next; we will apply foreground then background.
-        match &self.foreground {
+        match &foreground {
             ForegroundColor::Simple(color) => {
                 if [BLACK, RED, GREEN, YELLOW, BLUE, MAGENTA, CYAN, WHITE].contains(color) {
                     sequence.push(*color);
@@ -159,6 +173,11 @@ impl Display for StyledString {
                     sequence.push(*color);
                 }
             }
+            ForegroundColor::Indexed(index) => {
+                sequence.push(FOREGROUND);
+                sequence.push(LOW_DEPTH);
+                sequence.push(*index);
+            }
             ForegroundColor::Rgb(rgb) => {
                 sequence.push(FOREGROUND);
                 sequence.push(HIGH_DEPTH);
@@ -166,10 +185,10 @@ impl Display for StyledString {
                 sequence.push(rgb.green);
                 sequence.push(rgb.blue);
             }
-            ForegroundColor::Empty => {}
+            ForegroundColor::Empty | ForegroundColor::Adaptive(_) => {}
         }
 
-        match &self.background {
+        match &background {
             BackgroundColor::Simple(color) => {
                 if [BLACK, RED, GREEN, YELLOW, BLUE, MAGENTA, CYAN, WHITE].contains(color) {
                     sequence.push(*color + 10);
@@ -179,6 +198,11 @@ impl Display for StyledString {
                     sequence.push(*color);
                 }
             }
+            BackgroundColor::Indexed(index) => {
+                sequence.push(BACKGROUND);
+                sequence.push(LOW_DEPTH);
+                sequence.push(*index);
+            }
             BackgroundColor::Rgb(rgb) => {
                 sequence.push(BACKGROUND);
                 sequence.push(HIGH_DEPTH);
@@ -186,7 +210,7 @@ impl Display for StyledString {
                 sequence.push(rgb.green);
                 sequence.push(rgb.blue);
             }
-            BackgroundColor::Empty => {}
+            BackgroundColor::Empty | BackgroundColor::Adaptive(_) => {}
         }
 
         let delimited_sequence = sequence
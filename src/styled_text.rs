@@ -0,0 +1,82 @@
+use std::fmt::Display;
+
+use crate::{
+    background::BackgroundColor, foreground::ForegroundColor, styled_string::StyledString,
+};
+
+/// Represents text made up of multiple `StyledString` spans, each with their
+/// own styles. It is displayed by writing out every span in order, where
+/// spans without any styles are written as plain text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[must_use]
+pub struct StyledText {
+    pub(crate) spans: Vec<StyledString>,
+}
+
+impl StyledText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a span to the end of the text.
+    pub fn push(&mut self, span: impl Into<StyledString>) {
+        self.spans.push(span.into());
+    }
+
+    /// Returns the spans making up the text.
+    pub fn spans(&self) -> &[StyledString] {
+        &self.spans
+    }
+
+    /// Returns the text without any styles applied.
+    #[must_use]
+    pub fn plain_text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+}
+
+impl From<StyledString> for StyledText {
+    fn from(span: StyledString) -> Self {
+        Self { spans: vec![span] }
+    }
+}
+
+impl FromIterator<StyledString> for StyledText {
+    fn from_iter<I: IntoIterator<Item = StyledString>>(iter: I) -> Self {
+        Self {
+            spans: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<StyledString> for StyledText {
+    fn extend<I: IntoIterator<Item = StyledString>>(&mut self, iter: I) {
+        self.spans.extend(iter);
+    }
+}
+
+impl IntoIterator for StyledText {
+    type Item = StyledString;
+    type IntoIter = std::vec::IntoIter<StyledString>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.spans.into_iter()
+    }
+}
+
+impl Display for StyledText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for span in &self.spans {
+            if span.modes.is_empty()
+                && span.foreground == ForegroundColor::Empty
+                && span.background == BackgroundColor::Empty
+            {
+                write!(f, "{}", span.text)?;
+            } else {
+                write!(f, "{span}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
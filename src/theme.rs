@@ -0,0 +1,157 @@
+use std::sync::RwLock;
+
+use crate::{
+    background::BackgroundColor,
+    constants::{BLUE, CYAN, GREEN, MAGENTA, RED, YELLOW},
+    foreground::ForegroundColor,
+    style::Style,
+    styled_string::StyledString,
+};
+
+/// The names of the semantic roles of a `Theme`, in the order of its fields.
+pub const ROLES: [&str; 6] = ["error", "warning", "success", "info", "muted", "accent"];
+
+/// Maps semantic roles, such as errors and warnings, to styles. Styling text
+/// by its meaning rather than by its color lets an application change its
+/// whole look in a single place, or at runtime with `set_theme`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Used for failures that stopped an operation.
+    pub error: Style,
+    /// Used for problems that did not stop an operation.
+    pub warning: Style,
+    /// Used for operations that completed successfully.
+    pub success: Style,
+    /// Used for neutral, informational messages.
+    pub info: Style,
+    /// Used for secondary details, such as timestamps and hints.
+    pub muted: Style,
+    /// Used to draw attention, such as to names, paths and commands.
+    pub accent: Style,
+}
+
+impl Theme {
+    /// The built-in theme, which only uses the basic colors so that it follows
+    /// the palette of the terminal.
+    pub const DEFAULT: Theme = Theme {
+        error: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(RED)),
+        warning: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(YELLOW)),
+        success: Style::new().with_foreground(ForegroundColor::Simple(GREEN)),
+        info: Style::new().with_foreground(ForegroundColor::Simple(BLUE)),
+        muted: Style::new().dim(),
+        accent: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(CYAN)),
+    };
+
+    /// A theme without any colors, which only uses modes. Useful for
+    /// terminals or users that do not want colored output.
+    pub const MONOCHROME: Theme = Theme {
+        error: Style::new().bold().underline(),
+        warning: Style::new().bold(),
+        success: Style::new(),
+        info: Style::new(),
+        muted: Style::new().dim(),
+        accent: Style::new().italic(),
+    };
+
+    /// A theme that marks each role with a background color rather than the
+    /// text color, similar to badges or labels.
+    pub const BADGES: Theme = Theme {
+        error: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(15))
+            .with_background(BackgroundColor::Simple(RED)),
+        warning: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(0))
+            .with_background(BackgroundColor::Simple(YELLOW)),
+        success: Style::new()
+            .with_foreground(ForegroundColor::Simple(0))
+            .with_background(BackgroundColor::Simple(GREEN)),
+        info: Style::new()
+            .with_foreground(ForegroundColor::Simple(15))
+            .with_background(BackgroundColor::Simple(BLUE)),
+        muted: Style::new().dim(),
+        accent: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(15))
+            .with_background(BackgroundColor::Simple(MAGENTA)),
+    };
+
+    /// Returns the style of a role by its name, such as `"error"`, or `None`
+    /// if there is no such role. See `ROLES`.
+    #[must_use]
+    pub fn get(&self, role: &str) -> Option<Style> {
+        let style = match role {
+            "error" => self.error,
+            "warning" => self.warning,
+            "success" => self.success,
+            "info" => self.info,
+            "muted" => self.muted,
+            "accent" => self.accent,
+            _ => return None,
+        };
+
+        Some(style)
+    }
+
+    /// Styles the text as an error.
+    pub fn error(&self, text: impl AsRef<str>) -> StyledString {
+        self.error.apply(text)
+    }
+
+    /// Styles the text as a warning.
+    pub fn warning(&self, text: impl AsRef<str>) -> StyledString {
+        self.warning.apply(text)
+    }
+
+    /// Styles the text as a success.
+    pub fn success(&self, text: impl AsRef<str>) -> StyledString {
+        self.success.apply(text)
+    }
+
+    /// Styles the text as information.
+    pub fn info(&self, text: impl AsRef<str>) -> StyledString {
+        self.info.apply(text)
+    }
+
+    /// Styles the text as a secondary detail.
+    pub fn muted(&self, text: impl AsRef<str>) -> StyledString {
+        self.muted.apply(text)
+    }
+
+    /// Styles the text so that it stands out.
+    pub fn accent(&self, text: impl AsRef<str>) -> StyledString {
+        self.accent.apply(text)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// The theme returned by `theme`, shared by the whole application.
+static THEME: RwLock<Theme> = RwLock::new(Theme::DEFAULT);
+
+/// Replaces the theme shared by the whole application, which is returned by
+/// `theme`. Text styled afterwards uses the new theme.
+pub fn set_theme(theme: Theme) {
+    // A theme is always valid, so one left behind by a panicking thread can
+    // safely be replaced.
+    let mut current = THEME.write().unwrap_or_else(|error| error.into_inner());
+    *current = theme;
+}
+
+/// Returns the theme shared by the whole application, which is
+/// `Theme::DEFAULT` unless it was replaced with `set_theme`.
+#[must_use]
+pub fn theme() -> Theme {
+    *THEME.read().unwrap_or_else(|error| error.into_inner())
+}
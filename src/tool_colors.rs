@@ -0,0 +1,620 @@
+use std::{env, error::Error, fmt::Display};
+
+use crate::{
+    background::BackgroundColor,
+    color::Simple,
+    constants::{
+        BLINK, BLUE, BOLD, CYAN, DIM, GREEN, INVERT, ITALIC, MAGENTA, RED, STRIKE, UNDERLINE,
+    },
+    foreground::ForegroundColor,
+    style::{ParseStyleError, Style},
+};
+
+/// Represents an entry of `GREP_COLORS` or `GCC_COLORS`, or a line of a git
+/// configuration, which could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColorsError {
+    /// The line of the entry, which is always 1 for environment variables.
+    pub line: usize,
+    /// The entry or line, without comments.
+    pub entry: String,
+}
+
+impl Display for ColorsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid entry `{}` on line {}", self.entry, self.line)
+    }
+}
+
+impl Error for ColorsError {}
+
+/// The styles GNU `grep` uses for its output, as configured by the
+/// `GREP_COLORS` environment variable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GrepColors {
+    /// `ms`: matching text in a selected line.
+    pub selected_match: Style,
+    /// `mc`: matching text in a context line.
+    pub context_match: Style,
+    /// `sl`: the rest of a selected line.
+    pub selected_line: Style,
+    /// `cx`: the rest of a context line.
+    pub context_line: Style,
+    /// `fn`: file names.
+    pub file_name: Style,
+    /// `ln`: line numbers.
+    pub line_number: Style,
+    /// `bn`: byte offsets.
+    pub byte_offset: Style,
+    /// `se`: separators between fields and groups of lines.
+    pub separator: Style,
+    /// `rv`: swaps `sl` and `cx` when inverting the match with `-v`.
+    pub reverse: bool,
+    /// `ne`: disables the erase in line sequence after colored text.
+    pub no_erase: bool,
+}
+
+/// The keys of `GREP_COLORS` which hold styles, in the order `grep` lists
+/// them.
+const GREP_KEYS: [&str; 8] = ["ms", "mc", "sl", "cx", "fn", "ln", "bn", "se"];
+
+impl GrepColors {
+    /// The styles `grep` uses when `GREP_COLORS` is not set.
+    pub const DEFAULT: GrepColors = GrepColors {
+        selected_match: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(RED)),
+        context_match: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(RED)),
+        selected_line: Style::new(),
+        context_line: Style::new(),
+        file_name: Style::new().with_foreground(ForegroundColor::Simple(MAGENTA)),
+        line_number: Style::new().with_foreground(ForegroundColor::Simple(GREEN)),
+        byte_offset: Style::new().with_foreground(ForegroundColor::Simple(GREEN)),
+        separator: Style::new().with_foreground(ForegroundColor::Simple(CYAN)),
+        reverse: false,
+        no_erase: false,
+    };
+
+    /// Reads the `GREP_COLORS` environment variable, returning `None` if it
+    /// is not set or is invalid.
+    #[must_use]
+    pub fn from_env() -> Option<GrepColors> {
+        GrepColors::parse(&env::var("GREP_COLORS").ok()?).ok()
+    }
+
+    /// Parses the value of `GREP_COLORS`, such as `"ms=01;32:fn=34:ne"`.
+    /// Styles which are not given keep their defaults, and `mt` sets both
+    /// `ms` and `mc`.
+    pub fn parse(value: &str) -> Result<GrepColors, ColorsError> {
+        let mut colors = GrepColors::DEFAULT;
+
+        for entry in value.split(':').filter(|entry| !entry.is_empty()) {
+            let error = || ColorsError {
+                line: 1,
+                entry: entry.to_string(),
+            };
+
+            match entry.split_once('=') {
+                Some(("mt", sgr)) => {
+                    let style = Style::from_sgr(sgr).ok_or_else(error)?;
+                    colors.selected_match = style;
+                    colors.context_match = style;
+                }
+                Some((key, sgr)) => {
+                    if let Some(style) = colors.get_mut(key) {
+                        *style = Style::from_sgr(sgr).ok_or_else(error)?;
+                    }
+                }
+                None if entry == "rv" => colors.reverse = true,
+                None if entry == "ne" => colors.no_erase = true,
+                None => {}
+            }
+        }
+
+        Ok(colors)
+    }
+
+    /// Returns the style for a key of `GREP_COLORS`, such as `"ms"`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Style> {
+        let style = match key {
+            "ms" | "mt" => self.selected_match,
+            "mc" => self.context_match,
+            "sl" => self.selected_line,
+            "cx" => self.context_line,
+            "fn" => self.file_name,
+            "ln" => self.line_number,
+            "bn" => self.byte_offset,
+            "se" => self.separator,
+            _ => return None,
+        };
+
+        Some(style)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut Style> {
+        let style = match key {
+            "ms" => &mut self.selected_match,
+            "mc" => &mut self.context_match,
+            "sl" => &mut self.selected_line,
+            "cx" => &mut self.context_line,
+            "fn" => &mut self.file_name,
+            "ln" => &mut self.line_number,
+            "bn" => &mut self.byte_offset,
+            "se" => &mut self.separator,
+            _ => return None,
+        };
+
+        Some(style)
+    }
+}
+
+impl Default for GrepColors {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Displays the styles in the format of `GREP_COLORS`.
+impl Display for GrepColors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut entries: Vec<String> = GREP_KEYS
+            .iter()
+            .filter_map(|key| Some(format!("{key}={}", self.get(key)?.to_sgr())))
+            .collect();
+
+        if self.reverse {
+            entries.push(String::from("rv"));
+        }
+        if self.no_erase {
+            entries.push(String::from("ne"));
+        }
+
+        write!(f, "{}", entries.join(":"))
+    }
+}
+
+/// The styles GCC uses for its diagnostics, as configured by the
+/// `GCC_COLORS` environment variable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GccColors {
+    /// `error`: the `error:` label of error messages.
+    pub error: Style,
+    /// `warning`: the `warning:` label of warning messages.
+    pub warning: Style,
+    /// `note`: the `note:` label of notes attached to a diagnostic.
+    pub note: Style,
+    /// `range1`: the first underlined range of source code.
+    pub range1: Style,
+    /// `range2`: the second underlined range of source code.
+    pub range2: Style,
+    /// `locus`: the location of a diagnostic, such as `file.c:12:7`.
+    pub locus: Style,
+    /// `quote`: quoted source code in a message.
+    pub quote: Style,
+    /// `path`: the events of a path through the code.
+    pub path: Style,
+    /// `fixit-insert`: suggested text to insert.
+    pub fixit_insert: Style,
+    /// `fixit-delete`: suggested text to delete.
+    pub fixit_delete: Style,
+    /// `diff-filename`: file names in generated patches.
+    pub diff_filename: Style,
+    /// `diff-hunk`: hunk headers in generated patches.
+    pub diff_hunk: Style,
+    /// `diff-delete`: deleted lines in generated patches.
+    pub diff_delete: Style,
+    /// `diff-insert`: inserted lines in generated patches.
+    pub diff_insert: Style,
+    /// `type-diff`: the differences between two types.
+    pub type_diff: Style,
+}
+
+/// The keys of `GCC_COLORS`, in the order GCC documents them.
+const GCC_KEYS: [&str; 15] = [
+    "error",
+    "warning",
+    "note",
+    "range1",
+    "range2",
+    "locus",
+    "quote",
+    "path",
+    "fixit-insert",
+    "fixit-delete",
+    "diff-filename",
+    "diff-hunk",
+    "diff-delete",
+    "diff-insert",
+    "type-diff",
+];
+
+impl GccColors {
+    /// The styles GCC uses when `GCC_COLORS` is not set.
+    pub const DEFAULT: GccColors = GccColors {
+        error: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(RED)),
+        warning: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(MAGENTA)),
+        note: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(CYAN)),
+        range1: Style::new().with_foreground(ForegroundColor::Simple(GREEN)),
+        range2: Style::new().with_foreground(ForegroundColor::Simple(BLUE)),
+        locus: Style::new().bold(),
+        quote: Style::new().bold(),
+        path: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(CYAN)),
+        fixit_insert: Style::new().with_foreground(ForegroundColor::Simple(GREEN)),
+        fixit_delete: Style::new().with_foreground(ForegroundColor::Simple(RED)),
+        diff_filename: Style::new().bold(),
+        diff_hunk: Style::new().with_foreground(ForegroundColor::Simple(GREEN)),
+        diff_delete: Style::new().with_foreground(ForegroundColor::Simple(RED)),
+        diff_insert: Style::new().with_foreground(ForegroundColor::Simple(GREEN)),
+        type_diff: Style::new()
+            .bold()
+            .with_foreground(ForegroundColor::Simple(GREEN)),
+    };
+
+    /// Styles without any colors or modes, which is what GCC uses when
+    /// `GCC_COLORS` is set to an empty string.
+    pub const PLAIN: GccColors = GccColors {
+        error: Style::new(),
+        warning: Style::new(),
+        note: Style::new(),
+        range1: Style::new(),
+        range2: Style::new(),
+        locus: Style::new(),
+        quote: Style::new(),
+        path: Style::new(),
+        fixit_insert: Style::new(),
+        fixit_delete: Style::new(),
+        diff_filename: Style::new(),
+        diff_hunk: Style::new(),
+        diff_delete: Style::new(),
+        diff_insert: Style::new(),
+        type_diff: Style::new(),
+    };
+
+    /// Reads the `GCC_COLORS` environment variable, returning `None` if it is
+    /// not set or is invalid.
+    #[must_use]
+    pub fn from_env() -> Option<GccColors> {
+        GccColors::parse(&env::var("GCC_COLORS").ok()?).ok()
+    }
+
+    /// Parses the value of `GCC_COLORS`, such as `"error=01;31:note=36"`.
+    /// Styles which are not given keep their defaults, except that an empty
+    /// value disables every style, and unknown keys are ignored.
+    pub fn parse(value: &str) -> Result<GccColors, ColorsError> {
+        if value.is_empty() {
+            return Ok(GccColors::PLAIN);
+        }
+
+        let mut colors = GccColors::DEFAULT;
+
+        for entry in value.split(':').filter(|entry| !entry.is_empty()) {
+            let error = || ColorsError {
+                line: 1,
+                entry: entry.to_string(),
+            };
+
+            let (key, sgr) = entry.split_once('=').ok_or_else(error)?;
+            if let Some(style) = colors.get_mut(key) {
+                *style = Style::from_sgr(sgr).ok_or_else(error)?;
+            }
+        }
+
+        Ok(colors)
+    }
+
+    /// Returns the style for a key of `GCC_COLORS`, such as `"error"`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Style> {
+        let style = match key {
+            "error" => self.error,
+            "warning" => self.warning,
+            "note" => self.note,
+            "range1" => self.range1,
+            "range2" => self.range2,
+            "locus" => self.locus,
+            "quote" => self.quote,
+            "path" => self.path,
+            "fixit-insert" => self.fixit_insert,
+            "fixit-delete" => self.fixit_delete,
+            "diff-filename" => self.diff_filename,
+            "diff-hunk" => self.diff_hunk,
+            "diff-delete" => self.diff_delete,
+            "diff-insert" => self.diff_insert,
+            "type-diff" => self.type_diff,
+            _ => return None,
+        };
+
+        Some(style)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut Style> {
+        let style = match key {
+            "error" => &mut self.error,
+            "warning" => &mut self.warning,
+            "note" => &mut self.note,
+            "range1" => &mut self.range1,
+            "range2" => &mut self.range2,
+            "locus" => &mut self.locus,
+            "quote" => &mut self.quote,
+            "path" => &mut self.path,
+            "fixit-insert" => &mut self.fixit_insert,
+            "fixit-delete" => &mut self.fixit_delete,
+            "diff-filename" => &mut self.diff_filename,
+            "diff-hunk" => &mut self.diff_hunk,
+            "diff-delete" => &mut self.diff_delete,
+            "diff-insert" => &mut self.diff_insert,
+            "type-diff" => &mut self.type_diff,
+            _ => return None,
+        };
+
+        Some(style)
+    }
+}
+
+impl Default for GccColors {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Displays the styles in the format of `GCC_COLORS`.
+impl Display for GccColors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries: Vec<String> = GCC_KEYS
+            .iter()
+            .filter_map(|key| Some(format!("{key}={}", self.get(key)?.to_sgr())))
+            .collect();
+
+        write!(f, "{}", entries.join(":"))
+    }
+}
+
+impl Style {
+    /// Parses a color value of git's configuration, such as
+    /// `"bold red ul"`, `"nobold brightblue #202020"` or `"reset green"`.
+    /// The first color is the foreground and the second is the background.
+    pub fn from_git(value: &str) -> Result<Style, ParseStyleError> {
+        // `reset` clears the terminal's current style before applying the
+        // rest, which is what applying a style on its own does.
+        let words: Vec<&str> = value
+            .split_whitespace()
+            .filter(|word| !word.eq_ignore_ascii_case("reset"))
+            .collect();
+
+        words.join(" ").parse()
+    }
+
+    /// Returns the style as a color value for git's configuration, such as
+    /// `"bold ul brightred #202020"`. Hidden text and `Adaptive` colors are
+    /// not supported by git, so the mode is left out and the colors are
+    /// resolved.
+    #[must_use]
+    pub fn to_git(&self) -> String {
+        let mut words: Vec<String> = self
+            .mode_codes()
+            .filter_map(git_mode_name)
+            .map(String::from)
+            .collect();
+
+        words.extend(
+            self.disabled_codes()
+                .filter_map(git_mode_name)
+                .map(|name| format!("no{name}")),
+        );
+
+        let background = match self.background.resolved() {
+            BackgroundColor::Simple(color) => Some(ForegroundColor::Simple(color)),
+            BackgroundColor::Indexed(index) => Some(ForegroundColor::Indexed(index)),
+            BackgroundColor::Rgb(color) => Some(ForegroundColor::Rgb(color)),
+            BackgroundColor::Empty | BackgroundColor::Adaptive(_) => None,
+        };
+
+        match self.foreground.resolved() {
+            ForegroundColor::Empty | ForegroundColor::Adaptive(_) if background.is_some() => {
+                words.push(String::from("normal"));
+            }
+            ForegroundColor::Empty | ForegroundColor::Adaptive(_) => {}
+            color => words.push(git_color_word(color)),
+        }
+
+        if let Some(background) = background {
+            words.push(git_color_word(background));
+        }
+
+        words.join(" ")
+    }
+}
+
+/// Returns git's name for a mode, such as `"ul"` for `UNDERLINE`.
+fn git_mode_name(mode: u8) -> Option<&'static str> {
+    let name = match mode {
+        BOLD => "bold",
+        DIM => "dim",
+        ITALIC => "italic",
+        UNDERLINE => "ul",
+        BLINK => "blink",
+        INVERT => "reverse",
+        STRIKE => "strike",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Returns git's name for a color, such as `"brightred"`, `"208"` or
+/// `"#ff8800"`.
+fn git_color_word(color: ForegroundColor) -> String {
+    match color {
+        ForegroundColor::Simple(color) => match Simple(color).name() {
+            Some(name) => name.replace('-', ""),
+            None => color.to_string(),
+        },
+        ForegroundColor::Indexed(index) => index.to_string(),
+        ForegroundColor::Rgb(color) => color.to_hex(),
+        ForegroundColor::Empty | ForegroundColor::Adaptive(_) => String::from("normal"),
+    }
+}
+
+/// The color settings of a git configuration, such as `color.diff.meta`,
+/// mapped to styles.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GitColors {
+    // The keys without the `color.` prefix, such as `diff.meta`.
+    entries: Vec<(String, Style)>,
+}
+
+impl GitColors {
+    /// Parses the color settings of a git configuration file, such as
+    /// `meta = bold yellow` in a `[color "diff"]` section. Other sections,
+    /// and settings such as `color.ui = auto`, are ignored.
+    pub fn parse(config: &str) -> Result<GitColors, ColorsError> {
+        let mut colors = GitColors::default();
+        let mut section: Option<String> = None;
+
+        for (index, line) in config.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = || ColorsError {
+                line: index + 1,
+                entry: line.to_string(),
+            };
+
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header.strip_suffix(']').ok_or_else(error)?.trim();
+                section = color_subsection(header);
+                continue;
+            }
+
+            let Some(subsection) = &section else {
+                continue;
+            };
+
+            let (name, value) = line.split_once('=').ok_or_else(error)?;
+            let value = value.trim().trim_matches('"');
+            let style = Style::from_git(value).map_err(|_| error())?;
+
+            colors.set(
+                &format!("{subsection}.{}", name.trim().to_ascii_lowercase()),
+                style,
+            );
+        }
+
+        Ok(colors)
+    }
+
+    /// Returns the style of a setting, such as `"color.diff.meta"` or
+    /// `"diff.meta"`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Style> {
+        let key = normalize_key(key);
+
+        self.entries
+            .iter()
+            .find(|(existing, _)| *existing == key)
+            .map(|(_, style)| *style)
+    }
+
+    /// Sets the style of a setting, such as `"color.diff.meta"` or
+    /// `"diff.meta"`.
+    pub fn set(&mut self, key: &str, style: Style) {
+        let key = normalize_key(key);
+
+        match self
+            .entries
+            .iter_mut()
+            .find(|(existing, _)| *existing == key)
+        {
+            Some((_, existing)) => *existing = style,
+            None => self.entries.push((key, style)),
+        }
+    }
+}
+
+/// Displays the settings as a git configuration, with a section for each
+/// command.
+impl Display for GitColors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut subsections: Vec<&str> = Vec::new();
+        for (key, _) in &self.entries {
+            let (subsection, _) = key.rsplit_once('.').unwrap_or_default();
+            if !subsections.contains(&subsection) {
+                subsections.push(subsection);
+            }
+        }
+
+        for subsection in subsections {
+            writeln!(f, "[color \"{subsection}\"]")?;
+
+            for (key, style) in &self.entries {
+                if let Some((existing, name)) = key.rsplit_once('.') {
+                    if existing == subsection {
+                        // Hexadecimal colors would otherwise start a comment.
+                        let value = style.to_git();
+                        if value.contains('#') {
+                            writeln!(f, "\t{name} = \"{value}\"")?;
+                        } else {
+                            writeln!(f, "\t{name} = {value}")?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the subsection of a `[color "diff"]` or `[color.diff]` section
+/// header, or `None` for other sections.
+fn color_subsection(header: &str) -> Option<String> {
+    let (name, subsection) = match header.split_once(char::is_whitespace) {
+        Some((name, subsection)) => (name, subsection.trim().trim_matches('"').to_string()),
+        None => {
+            let (name, subsection) = header.split_once('.')?;
+            (name, subsection.to_ascii_lowercase())
+        }
+    };
+
+    name.eq_ignore_ascii_case("color").then_some(subsection)
+}
+
+/// Removes a `#` or `;` comment from a line of a git configuration, unless
+/// it is quoted, as in `meta = "#ff8800"`.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+
+    for (index, character) in line.char_indices() {
+        match character {
+            '"' => quoted = !quoted,
+            '#' | ';' if !quoted => return &line[..index],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+/// Removes the `color.` prefix of a key, and lowercases the variable name,
+/// which is case-insensitive in git.
+fn normalize_key(key: &str) -> String {
+    let key = key.strip_prefix("color.").unwrap_or(key);
+
+    match key.rsplit_once('.') {
+        Some((subsection, name)) => format!("{subsection}.{}", name.to_ascii_lowercase()),
+        None => key.to_ascii_lowercase(),
+    }
+}
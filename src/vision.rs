@@ -0,0 +1,217 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{
+    background::BackgroundColor,
+    color::Rgb,
+    color_space::{from_linear_rgb, linear_rgb},
+    foreground::ForegroundColor,
+    style::Style,
+    styled_string::StyledString,
+    styled_text::StyledText,
+    theme::Theme,
+};
+
+/// Represents a type of color vision deficiency (color blindness).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Deficiency {
+    /// No functioning red cones; reds appear dark and are confused with
+    /// greens.
+    Protanopia,
+    /// No functioning green cones; the most common form of red-green color
+    /// blindness.
+    Deuteranopia,
+    /// No functioning blue cones; blues are confused with greens, and yellows
+    /// with pinks.
+    Tritanopia,
+    /// No color vision at all; only lightness can be told apart.
+    Achromatopsia,
+}
+
+// Simulation matrices for linear RGB from "A Physiologically-based Model for
+// Simulation of Color Vision Deficiency" by Machado, Oliveira and Fernandes
+// (2009), at full severity.
+const PROTANOPIA: [[f64; 3]; 3] = [
+    [0.152_286, 1.052_583, -0.204_868],
+    [0.114_503, 0.786_281, 0.099_216],
+    [-0.003_882, -0.048_116, 1.051_998],
+];
+
+const DEUTERANOPIA: [[f64; 3]; 3] = [
+    [0.367_322, 0.860_646, -0.227_968],
+    [0.280_085, 0.672_501, 0.047_413],
+    [-0.011_820, 0.042_940, 0.968_881],
+];
+
+const TRITANOPIA: [[f64; 3]; 3] = [
+    [1.255_528, -0.076_749, -0.178_779],
+    [-0.078_411, 0.930_809, 0.147_602],
+    [0.004_733, 0.691_367, 0.303_900],
+];
+
+const ACHROMATOPSIA: [[f64; 3]; 3] = [
+    [0.212_6, 0.715_2, 0.072_2],
+    [0.212_6, 0.715_2, 0.072_2],
+    [0.212_6, 0.715_2, 0.072_2],
+];
+
+// Error redistribution matrices used for daltonization, which shift the
+// information lost by the deficiency into channels that can still be seen.
+const RED_GREEN_SHIFT: [[f64; 3]; 3] = [[0.0, 0.0, 0.0], [0.7, 1.0, 0.0], [0.7, 0.0, 1.0]];
+const BLUE_YELLOW_SHIFT: [[f64; 3]; 3] = [[1.0, 0.0, 0.7], [0.0, 1.0, 0.7], [0.0, 0.0, 0.0]];
+
+impl Deficiency {
+    fn matrix(&self) -> &'static [[f64; 3]; 3] {
+        match self {
+            Deficiency::Protanopia => &PROTANOPIA,
+            Deficiency::Deuteranopia => &DEUTERANOPIA,
+            Deficiency::Tritanopia => &TRITANOPIA,
+            Deficiency::Achromatopsia => &ACHROMATOPSIA,
+        }
+    }
+}
+
+fn multiply(matrix: &[[f64; 3]; 3], vector: [f64; 3]) -> [f64; 3] {
+    matrix.map(|row| row[0] * vector[0] + row[1] * vector[1] + row[2] * vector[2])
+}
+
+impl Rgb {
+    /// Returns the color as it would be seen by someone with the given color
+    /// vision deficiency.
+    #[must_use]
+    pub fn simulate(self, deficiency: Deficiency) -> Self {
+        from_linear_rgb(multiply(deficiency.matrix(), linear_rgb(self)))
+    }
+
+    /// Returns a corrected version of the color, which shifts the differences
+    /// lost to the given color vision deficiency into colors that can still be
+    /// told apart. Achromatopsia cannot be corrected, so the color is
+    /// returned unchanged.
+    #[must_use]
+    pub fn daltonize(self, deficiency: Deficiency) -> Self {
+        let shift = match deficiency {
+            Deficiency::Protanopia | Deficiency::Deuteranopia => &RED_GREEN_SHIFT,
+            Deficiency::Tritanopia => &BLUE_YELLOW_SHIFT,
+            Deficiency::Achromatopsia => return self,
+        };
+
+        let original = linear_rgb(self);
+        let simulated = multiply(deficiency.matrix(), original);
+        let error = [
+            original[0] - simulated[0],
+            original[1] - simulated[1],
+            original[2] - simulated[2],
+        ];
+        let correction = multiply(shift, error);
+
+        from_linear_rgb([
+            original[0] + correction[0],
+            original[1] + correction[1],
+            original[2] + correction[2],
+        ])
+    }
+}
+
+impl ForegroundColor {
+    /// Returns the color as it would be seen with the given color vision
+    /// deficiency. `Simple` colors are approximated using the xterm palette.
+    #[must_use]
+    pub fn simulate(self, deficiency: Deficiency) -> Self {
+        match self.to_rgb() {
+            Some(color) => ForegroundColor::Rgb(color.simulate(deficiency)),
+            None => self,
+        }
+    }
+}
+
+impl BackgroundColor {
+    /// Returns the color as it would be seen with the given color vision
+    /// deficiency. `Simple` colors are approximated using the xterm palette.
+    #[must_use]
+    pub fn simulate(self, deficiency: Deficiency) -> Self {
+        match self.to_rgb() {
+            Some(color) => BackgroundColor::Rgb(color.simulate(deficiency)),
+            None => self,
+        }
+    }
+}
+
+impl Style {
+    /// Returns the style as it would be seen with the given color vision
+    /// deficiency.
+    #[must_use]
+    pub fn simulate(mut self, deficiency: Deficiency) -> Self {
+        self.foreground = self.foreground.simulate(deficiency);
+        self.background = self.background.simulate(deficiency);
+        self
+    }
+}
+
+impl Theme {
+    /// Returns the theme as it would be seen with the given color vision
+    /// deficiency, such as to check that its roles can still be told apart.
+    #[must_use]
+    pub fn simulate(self, deficiency: Deficiency) -> Self {
+        Theme {
+            error: self.error.simulate(deficiency),
+            warning: self.warning.simulate(deficiency),
+            success: self.success.simulate(deficiency),
+            info: self.info.simulate(deficiency),
+            muted: self.muted.simulate(deficiency),
+            accent: self.accent.simulate(deficiency),
+        }
+    }
+}
+
+impl StyledString {
+    /// Returns the string as it would be seen with the given color vision
+    /// deficiency.
+    #[must_use = "this returns the simulated string, without changing the original"]
+    pub fn simulate(mut self, deficiency: Deficiency) -> Self {
+        self.foreground = self.foreground.simulate(deficiency);
+        self.background = self.background.simulate(deficiency);
+        self
+    }
+}
+
+impl StyledText {
+    /// Returns the text as it would be seen with the given color vision
+    /// deficiency.
+    #[must_use = "this returns the simulated text, without changing the original"]
+    pub fn simulate(self, deficiency: Deficiency) -> Self {
+        self.into_iter()
+            .map(|span| span.simulate(deficiency))
+            .collect()
+    }
+}
+
+// The deficiency simulated for all rendered output, stored as its index plus
+// one, where zero means no simulation.
+static PREVIEW: AtomicU8 = AtomicU8::new(0);
+
+/// Simulates a color vision deficiency for everything displayed by this
+/// library from now on, or turns the simulation off with `None`. This makes
+/// it easy to preview a whole application as a colorblind user would see it.
+pub fn set_preview(deficiency: Option<Deficiency>) {
+    let value = match deficiency {
+        None => 0,
+        Some(Deficiency::Protanopia) => 1,
+        Some(Deficiency::Deuteranopia) => 2,
+        Some(Deficiency::Tritanopia) => 3,
+        Some(Deficiency::Achromatopsia) => 4,
+    };
+
+    PREVIEW.store(value, Ordering::Relaxed);
+}
+
+/// Returns the color vision deficiency currently being simulated for all
+/// displayed output, if any. See `set_preview`.
+#[must_use]
+pub fn preview() -> Option<Deficiency> {
+    match PREVIEW.load(Ordering::Relaxed) {
+        1 => Some(Deficiency::Protanopia),
+        2 => Some(Deficiency::Deuteranopia),
+        3 => Some(Deficiency::Tritanopia),
+        4 => Some(Deficiency::Achromatopsia),
+        _ => None,
+    }
+}
@@ -0,0 +1,33 @@
+// The appearance is global, so it is tested in its own binary where it cannot
+// change the output of tests running in parallel.
+
+use iridescent::{appearance, set_appearance, Adaptive, Appearance, ForegroundColor, Rgb, Styled};
+
+#[test]
+fn test_global_appearance() {
+    let light = Rgb::new(0, 90, 160);
+    let dark = Rgb::new(120, 190, 255);
+    let link = Adaptive::new(light, dark);
+    let styled = "docs"
+        .foreground(link)
+        .background(Adaptive::new(dark, light));
+
+    set_appearance(Some(Appearance::Light));
+    assert_eq!(appearance(), Appearance::Light);
+    assert_eq!(
+        styled.to_string(),
+        "\x1b[38;2;0;90;160;48;2;120;190;255mdocs\x1b[0m"
+    );
+
+    set_appearance(Some(Appearance::Dark));
+    assert_eq!(appearance(), Appearance::Dark);
+    assert_eq!(ForegroundColor::from(link).to_rgb(), Some(dark));
+    assert_eq!(
+        styled.to_string(),
+        "\x1b[38;2;120;190;255;48;2;0;90;160mdocs\x1b[0m"
+    );
+
+    // Without a configured appearance, only the environment is used.
+    set_appearance(None);
+    assert_eq!(appearance(), Appearance::detect());
+}
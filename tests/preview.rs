@@ -0,0 +1,25 @@
+// The preview is global, so it is tested in its own binary where it cannot
+// change the output of tests running in parallel.
+
+use iridescent::{preview, set_preview, Deficiency, Rgb, Styled};
+
+#[test]
+fn test_preview() {
+    let styled = "status"
+        .foreground(Rgb::new(200, 40, 40))
+        .background(Rgb::new(40, 160, 40));
+    let original = styled.to_string();
+    let simulated = styled
+        .clone()
+        .simulate(Deficiency::Deuteranopia)
+        .to_string();
+    assert_ne!(simulated, original);
+
+    set_preview(Some(Deficiency::Deuteranopia));
+    assert_eq!(preview(), Some(Deficiency::Deuteranopia));
+    assert_eq!(styled.to_string(), simulated);
+
+    set_preview(None);
+    assert_eq!(preview(), None);
+    assert_eq!(styled.to_string(), original);
+}
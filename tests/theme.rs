@@ -0,0 +1,20 @@
+// The theme is global, so it is tested in its own binary where it cannot
+// change the output of tests running in parallel.
+
+use iridescent::{set_theme, theme, Rgb, Style, Theme};
+
+#[test]
+fn test_global_theme() {
+    let custom = Theme {
+        error: Style::new().foreground(Rgb::new(255, 85, 85)),
+        ..Theme::MONOCHROME
+    };
+
+    assert_eq!(theme(), Theme::DEFAULT);
+    set_theme(custom);
+    assert_eq!(theme(), custom);
+    assert_eq!(theme().error("failed"), custom.error("failed"));
+
+    set_theme(Theme::DEFAULT);
+    assert_eq!(theme(), Theme::DEFAULT);
+}